@@ -1,209 +1,4966 @@
+use arrayvec::ArrayVec;
 use criterion::{
-    black_box, criterion_group, criterion_main, measurement::Measurement, BatchSize,
-    BenchmarkGroup, BenchmarkId, Criterion, PlotConfiguration,
+    black_box, measurement::Measurement, BatchSize, BenchmarkGroup, BenchmarkId, Criterion,
+    PlotConfiguration,
 };
+use crossbeam_skiplist::SkipSet;
 use float_ord::FloatOrd;
-use rand::Rng;
-use spp_experiments::Float;
-use std::collections::{BTreeSet, HashSet, LinkedList, VecDeque};
-use std::iter::{self, FromIterator};
-
-fn human_readable_size(size_bytes: usize) -> String {
-    if size_bytes < 1024 {
-        size_bytes.to_string() + " bytes"
-    } else if size_bytes < 1024 * 1024 {
-        (size_bytes / 1024).to_string() + " kB"
-    } else if size_bytes < 1024 * 1024 * 1024 {
-        (size_bytes / 1024 / 1024).to_string() + " MB"
-    } else if size_bytes < 1024 * 1024 * 1024 {
-        (size_bytes / 1024 / 1024).to_string() + " GB"
+use indexmap::IndexSet;
+use num_complex::Complex;
+use ordered_float::{NotNan, OrderedFloat};
+#[cfg(all(feature = "pprof", target_os = "linux"))]
+use pprof::criterion::{Output, PProfProfiler};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use rayon::iter::IntoParallelRefIterator;
+use regex::Regex;
+#[cfg(feature = "decimal")]
+use rust_decimal::Decimal;
+use slab::Slab;
+use slotmap::SlotMap;
+use smallvec::SmallVec;
+#[cfg(target_arch = "aarch64")]
+use spp_experiments::aarch64;
+#[cfg(feature = "decimal")]
+use spp_experiments::decimal;
+#[cfg(feature = "fixed")]
+use spp_experiments::fixed_point;
+#[cfg(feature = "gpu")]
+use spp_experiments::gpu;
+#[cfg(all(feature = "perf", target_os = "linux"))]
+use spp_experiments::perf;
+#[cfg(all(feature = "rapl", target_os = "linux"))]
+use spp_experiments::rapl;
+#[cfg(feature = "simd")]
+use spp_experiments::simd;
+use spp_experiments::{
+    affinity, aligned,
+    checksum::{self, Checked, Expected, NoSum},
+    cpufreq,
+    data::{
+        self, create_distinct_data, create_distinct_data_with, create_distinct_ints,
+        create_non_finite_data, create_ordered_data, create_scrambled_bytes,
+        create_scrambled_complex, create_scrambled_data, create_scrambled_data_with,
+        create_subnormal_data, Distribution, Order,
+    },
+    evict::{self, Evictor},
+    experiment::{Experiment, ThroughputUnit},
+    finite, ftz, human_readable_size, layout,
+    list::{ArenaList, UnrolledList},
+    memory, metadata, mmap, parallel, quantized, replicas, residency, results,
+    sampling::Sampling,
+    scaling,
+    schedule::{self, Schedule},
+    sweep::{self, Sweep},
+    thermal, topology, unroll, Bits, Element, Int, IntPrimitive, OrderedElement, Primitive,
+};
+#[cfg(target_arch = "x86_64")]
+use spp_experiments::{tsc, x86};
+use std::cell::LazyCell;
+use std::collections::hash_map::RandomState;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque};
+use std::rc::Rc;
+use std::sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    Arc, Mutex, OnceLock,
+};
+use std::time::{Duration, Instant};
+use std::{convert::TryInto, env, iter, process};
+use typed_arena::Arena;
+
+#[cfg(feature = "dhat")]
+#[global_allocator]
+static ALLOC: dhat::Alloc = dhat::Alloc;
+
+/// The sweep of the run, resolved from the experiment, the environment and
+/// the detected caches, see [`Sweep::resolve`].
+static SWEEP: OnceLock<Sweep> = OnceLock::new();
+
+fn sweep() -> Sweep {
+    *SWEEP.get_or_init(|| {
+        Sweep::resolve(experiment(), &cache_capacities()).unwrap_or_else(|e| {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        })
+    })
+}
+
+fn start_pow() -> u32 {
+    sweep().start_pow
+}
+
+fn end_pow() -> u32 {
+    sweep().end_pow
+}
+
+fn step_pow() -> u32 {
+    sweep().step_pow
+}
+
+/// Data caches of the machine, detected on first use.
+static CACHES: OnceLock<Vec<topology::Cache>> = OnceLock::new();
+
+fn cache_capacities() -> Vec<usize> {
+    CACHES
+        .get_or_init(detect_caches)
+        .iter()
+        .map(|cache| cache.size_bytes)
+        .collect()
+}
+
+/// Detect the data caches and record them, or fall back to the caches of the
+/// test platform if they can't be detected.
+fn detect_caches() -> Vec<topology::Cache> {
+    let caches = match topology::data_caches() {
+        Ok(caches) if !caches.is_empty() => caches,
+        result => {
+            let reason = result
+                .err()
+                .map_or("none found".to_string(), |e| e.to_string());
+            eprintln!(
+                "warning: could not detect the caches ({}), assuming those of the test platform",
+                reason
+            );
+            metadata::record(metadata::RUN, "caches_assumed", true);
+            sweep::TEST_PLATFORM_CACHES
+                .iter()
+                .zip(1..)
+                .map(|(&size_bytes, level)| topology::Cache { level, size_bytes })
+                .collect()
+        }
+    };
+
+    let hierarchy = caches
+        .iter()
+        .map(|cache| format!("L{} {}", cache.level, human_readable_size(cache.size_bytes)))
+        .collect::<Vec<_>>();
+    metadata::record(metadata::RUN, "caches", hierarchy.join(", "));
+
+    caches
+}
+
+/// The experiment configuration, loaded in `main`.
+static EXPERIMENT: OnceLock<Experiment> = OnceLock::new();
+
+fn experiment() -> &'static Experiment {
+    EXPERIMENT.get_or_init(Experiment::default)
+}
+
+/// Load the experiment configuration, if any, and record where it came from.
+fn load_experiment() {
+    match Experiment::load() {
+        Ok(experiment) => {
+            if let Some(path) = &experiment.path {
+                metadata::record(metadata::RUN, "experiment", path.display());
+            }
+            if let Some(containers) = &experiment.containers {
+                metadata::record(metadata::RUN, "containers", containers.join(","));
+            }
+            if let Some(kernels) = &experiment.kernels {
+                metadata::record(metadata::RUN, "kernels", kernels.join(","));
+            }
+            if experiment.throughput == ThroughputUnit::Elements {
+                metadata::record(metadata::RUN, "throughput", "elements");
+            }
+            data::set_seed(experiment.seed.unwrap_or(data::DEFAULT_SEED));
+            metadata::record(metadata::RUN, "seed", data::seed());
+            EXPERIMENT
+                .set(experiment)
+                .expect("the experiment is only loaded once");
+        }
+        Err(e) => {
+            eprintln!("error: could not read the experiment: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Detect the caches the sweep is derived from, check that the sweep bounds
+/// make sense and record them, along with whether the sampling scales with
+/// them.
+fn configure_sweep() {
+    CACHES.get_or_init(detect_caches);
+    let (start_pow, end_pow, step_pow) = (start_pow(), end_pow(), step_pow());
+    metadata::record(metadata::RUN, "start_pow", start_pow);
+    metadata::record(metadata::RUN, "end_pow", end_pow);
+    metadata::record(metadata::RUN, "step_pow", step_pow);
+    if let Some(capacities) = cache_boundaries() {
+        let capacities = capacities
+            .iter()
+            .map(|&capacity| human_readable_size(capacity))
+            .collect::<Vec<_>>();
+        metadata::record(metadata::RUN, "cache_boundaries", capacities.join(","));
+    }
+    metadata::record(metadata::RUN, "scaled_sampling", scaled_sampling());
+    if quick() {
+        metadata::record(metadata::RUN, "quick", true);
+    }
+    if cold_cache() {
+        metadata::record(metadata::RUN, "cold_cache", true);
+        metadata::record(metadata::RUN, "evict_buffer_bytes", evict_buffer_bytes());
+    }
+    if allocations() > 1 {
+        metadata::record(metadata::RUN, "allocations", allocations());
+    }
+}
+
+/// Input sizes in bytes: every `step_pow2`th power of two from `2^start_pow2`
+/// up to `2^end_pow2`, and the sizes around the cache boundaries if enabled.
+/// In quick mode, only the smallest and the largest power of two, see
+/// [`quick_end_pow`]. The sizes are in ascending order unless the run is
+/// shuffled.
+fn input_sizes(start_pow2: u32, end_pow2: u32, step_pow2: u32) -> impl Iterator<Item = usize> {
+    let quick = quick();
+    let end_pow2 = if quick {
+        quick_end_pow(start_pow2, end_pow2)
     } else {
-        size_bytes.to_string() + " ??"
+        end_pow2
+    };
+    let pows = (start_pow2..=end_pow2)
+        .step_by(step_pow2 as usize)
+        .collect::<Vec<_>>();
+    let last = pows.len().saturating_sub(1);
+
+    let mut sizes = pows
+        .into_iter()
+        .enumerate()
+        .filter(|&(i, _)| !quick || i == 0 || i == last)
+        .map(|(_, pow2)| 2usize.pow(pow2))
+        .collect::<Vec<_>>();
+    if let (Some(capacities), false) = (cache_boundaries(), quick) {
+        let (min, max) = (2usize.pow(start_pow2), 2usize.pow(end_pow2));
+        sizes.extend(sweep::boundary_sizes(&capacities, min, max));
+        sizes.sort_unstable();
+        sizes.dedup();
+    }
+    shuffle(&mut sizes);
+
+    sizes.into_iter()
+}
+
+/// Cache capacities to add sizes just below and above to the sweep: those of
+/// the detected caches, unless `SOS_CACHE_BOUNDARIES` is set to a
+/// comma-separated list of sizes like `32k,256k,16M`, or to `none` for only
+/// the powers of two.
+fn cache_boundaries() -> Option<Vec<usize>> {
+    let capacities = match env::var("SOS_CACHE_BOUNDARIES") {
+        Ok(capacities) => capacities,
+        Err(_) => return Some(cache_capacities()),
+    };
+    match capacities.trim() {
+        "" => return Some(cache_capacities()),
+        "none" => return None,
+        _ => {}
+    }
+
+    let capacities = capacities
+        .split(',')
+        .map(|capacity| {
+            sweep::parse_size(capacity).unwrap_or_else(|| {
+                eprintln!("error: invalid SOS_CACHE_BOUNDARIES size: {}", capacity);
+                process::exit(1);
+            })
+        })
+        .collect();
+    Some(capacities)
+}
+
+/// Set `SOS_QUICK` for a smoke test of every benchmark: only the smallest and
+/// the largest input size, the latter capped by [`quick_end_pow`], with as few
+/// samples as criterion allows, flat sampling and a short analysis.
+fn quick() -> bool {
+    env::var_os("SOS_QUICK").is_some()
+}
+
+/// Largest power of two of the quick mode: the first one above the largest
+/// cache, but at most `QUICK_MAX_BYTES`, and within the sweep.
+fn quick_end_pow(start_pow2: u32, end_pow2: u32) -> u32 {
+    let above_caches = cache_capacities()
+        .into_iter()
+        .max()
+        .map_or(end_pow2, |largest| {
+            (largest + 1).next_power_of_two().ilog2()
+        });
+
+    above_caches
+        .min(QUICK_MAX_BYTES.ilog2())
+        .min(end_pow2)
+        .max(start_pow2)
+}
+
+/// Create the group `name` with the settings every group shares: a logarithmic
+/// summary plot, as the sizes grow geometrically, and linear sampling, which
+/// the sizes may override, or flat sampling in quick mode.
+fn new_group<'a, M: Measurement>(c: &'a mut Criterion<M>, name: &str) -> BenchmarkGroup<'a, M> {
+    *GROUP.lock().unwrap() = name.to_string();
+    let mut group = c.benchmark_group(name);
+    let conf = PlotConfiguration::default().summary_scale(criterion::AxisScale::Logarithmic);
+    group.plot_config(conf);
+    group.sampling_mode(if quick() {
+        criterion::SamplingMode::Flat
+    } else {
+        criterion::SamplingMode::Linear
+    });
+
+    group
+}
+
+/// Registers a case of a container matrix as `(ds_name, parameter_name,
+/// data_len, group)`.
+type Case<M> = fn(&str, &str, usize, &mut BenchmarkGroup<M>);
+
+/// The containers of the main matrix as `(ds_name, case)` pairs, named
+/// `container (kernel)`. The case of each container is `case` with the
+/// container of `elem`s as the type argument between `before` and `after`:
+/// `container_cases!("by value", bench_by_val_in_group, V, [V], [M, P])` runs
+/// `bench_by_val_in_group::<V, Vec<V>, M, P>` as `Vec (by value)` and so on.
+macro_rules! container_cases {
+    ($kernel:literal, $case:ident, $elem:ty, [$($before:ty),*], [$($after:ty),*]) => {
+        vec![
+            container_cases!(@case $kernel, "Vec", $case, [$($before),*], Vec<$elem>, [$($after),*]),
+            container_cases!(@case $kernel, "Box<[_]>", $case, [$($before),*], Box<[$elem]>, [$($after),*]),
+            container_cases!(@case $kernel, "SmallVec<16>", $case, [$($before),*], SmallVec<[$elem; 16]>, [$($after),*]),
+            container_cases!(@case $kernel, "SmallVec<1024>", $case, [$($before),*], SmallVec<[$elem; 1024]>, [$($after),*]),
+            container_cases!(@case $kernel, "VecDeque", $case, [$($before),*], VecDeque<$elem>, [$($after),*]),
+            container_cases!(@case $kernel, "im::Vector", $case, [$($before),*], im::Vector<$elem>, [$($after),*]),
+            container_cases!(@case $kernel, "LinkedList", $case, [$($before),*], LinkedList<$elem>, [$($after),*]),
+            container_cases!(@case $kernel, "BinaryHeap", $case, [$($before),*], BinaryHeap<$elem>, [$($after),*]),
+            container_cases!(@case $kernel, "HashSet", $case, [$($before),*], HashSet<$elem>, [$($after),*]),
+            container_cases!(@case $kernel, "hashbrown::HashSet", $case, [$($before),*], hashbrown::HashSet<$elem>, [$($after),*]),
+            container_cases!(@case $kernel, "IndexSet", $case, [$($before),*], IndexSet<$elem>, [$($after),*]),
+            container_cases!(@case $kernel, "BTreeSet", $case, [$($before),*], BTreeSet<$elem>, [$($after),*]),
+        ]
+    };
+    (@case $kernel:literal, $container:literal, $case:ident, [$($before:ty),*], $t:ty, [$($after:ty),*]) => {
+        (
+            concat!($container, " (", $kernel, ")"),
+            $case::<$($before,)* $t, $($after),*> as Case<_>,
+        )
+    };
+}
+
+/// Register `cases` over an input of `data_len` elements, in a random order if
+/// the run is shuffled and once per allocation, see [`replicate`].
+fn bench_cases<M: Measurement>(
+    mut cases: Vec<(&str, Case<M>)>,
+    input_bytes_human: &str,
+    data_len: usize,
+    group: &mut BenchmarkGroup<M>,
+) {
+    shuffle(&mut cases);
+
+    for (ds_name, case) in cases {
+        replicate(ds_name, |ds_name| {
+            case(ds_name, input_bytes_human, data_len, group)
+        });
+    }
+}
+
+/// Run `cases` in the group `name` over every input size of the sweep given
+/// as `(start_pow2, end_pow2, step_pow2)`, with elements of `element_bytes`.
+fn compare_cases<M: Measurement>(
+    name: &str,
+    element_bytes: usize,
+    (start_pow2, end_pow2, step_pow2): (u32, u32, u32),
+    c: &mut Criterion<M>,
+    cases: Vec<(&'static str, Case<M>)>,
+) {
+    let mut group = new_group(c, name);
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / element_bytes;
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        bench_cases(cases.clone(), &input_bytes_human, data_len, &mut group);
+    }
+
+    group.finish();
+}
+
+/// Set the throughput of the following cases in `group` to an input of
+/// `input_size_bytes` holding `elements`, and scale their sampling with the
+/// size, unless the sampling is configured otherwise.
+fn configure_for_size<M: Measurement>(
+    group: &mut BenchmarkGroup<M>,
+    input_size_bytes: usize,
+    elements: usize,
+) {
+    set_throughput(group, input_size_bytes, elements);
+    if !scaled_sampling() {
+        return;
+    }
+
+    let sampling = Sampling {
+        warm_up_time: uniform_sampling().warm_up_time,
+        ..Sampling::for_size(input_size_bytes)
+    };
+    group
+        .sample_size(sampling.sample_size as usize)
+        .measurement_time(sampling.measurement_time)
+        .sampling_mode(if sampling.flat {
+            criterion::SamplingMode::Flat
+        } else {
+            criterion::SamplingMode::Linear
+        });
+    *SAMPLING.lock().unwrap() = sampling;
+}
+
+/// Set the throughput of the following cases in `group`, in bytes or in
+/// elements as the experiment selects. Groups comparing element types of
+/// different sizes at the same input size set it again for each element type.
+fn set_throughput<M: Measurement>(
+    group: &mut BenchmarkGroup<M>,
+    input_size_bytes: usize,
+    elements: usize,
+) {
+    INPUT_BYTES.store(input_size_bytes, Ordering::Relaxed);
+    INPUT_ELEMENTS.store(elements, Ordering::Relaxed);
+    group.throughput(match experiment().throughput {
+        ThroughputUnit::Bytes => criterion::Throughput::Bytes(input_size_bytes as u64),
+        ThroughputUnit::Elements => criterion::Throughput::Elements(elements as u64),
+    });
+}
+
+/// The sampling scales with the input size unless the quick mode, the
+/// experiment or the command line sets the sample size or measurement time,
+/// which the settings of a group would override, or `SOS_UNIFORM_SAMPLING` is
+/// set.
+fn scaled_sampling() -> bool {
+    let experiment = experiment();
+    !quick()
+        && experiment.sample_size.is_none()
+        && experiment.measurement_time.is_none()
+        && env::var_os("SOS_UNIFORM_SAMPLING").is_none()
+        && !env::args()
+            .any(|arg| arg.starts_with("--sample-size") || arg.starts_with("--measurement-time"))
+}
+
+/// Sampling of every case when it isn't scaled, with the settings of the
+/// experiment and the quick mode as [`configure_from_experiment`] and
+/// [`configure_quick`] apply them.
+fn uniform_sampling() -> Sampling {
+    if quick() {
+        return Sampling {
+            sample_size: QUICK_SAMPLE_SIZE as u64,
+            measurement_time: QUICK_MEASUREMENT_TIME,
+            warm_up_time: QUICK_WARM_UP_TIME,
+            flat: true,
+            report_time: QUICK_REPORT_TIME,
+        };
+    }
+
+    let experiment = experiment();
+    Sampling {
+        sample_size: experiment
+            .sample_size
+            .map_or(Sampling::DEFAULT.sample_size, |n| n as u64),
+        measurement_time: experiment
+            .measurement_time
+            .unwrap_or(Sampling::DEFAULT.measurement_time),
+        warm_up_time: experiment
+            .warm_up_time
+            .unwrap_or(Sampling::DEFAULT.warm_up_time),
+        ..Sampling::DEFAULT
+    }
+}
+
+/// Random order of the run, if `SOS_SHUFFLE` is set.
+static SHUFFLE: Mutex<Option<StdRng>> = Mutex::new(None);
+
+/// Set `SOS_SHUFFLE` to a seed to run the groups, their input sizes and the
+/// containers of the main matrices in a random order, so that thermal drift
+/// over a long run doesn't systematically favour the cases that run first.
+/// With an empty value, a seed is drawn at random. The seed is recorded either
+/// way, so that the order can be repeated.
+fn configure_shuffle() {
+    let seed = match env::var("SOS_SHUFFLE") {
+        Ok(seed) if seed.is_empty() => rand::thread_rng().gen(),
+        Ok(seed) => seed.parse().unwrap_or_else(|e| {
+            eprintln!("error: invalid SOS_SHUFFLE: {}: {}", seed, e);
+            process::exit(1);
+        }),
+        Err(_) => return,
+    };
+
+    eprintln!("Shuffling the benchmarks with seed {}", seed);
+    metadata::record(metadata::RUN, "shuffle_seed", seed);
+    *SHUFFLE.lock().unwrap() = Some(StdRng::seed_from_u64(seed));
+}
+
+/// Shuffle `items` if the run is shuffled.
+fn shuffle<T>(items: &mut [T]) {
+    if let Some(rng) = SHUFFLE.lock().unwrap().as_mut() {
+        items.shuffle(rng);
+    }
+}
+
+// Criterion settings of the quick mode
+const QUICK_SAMPLE_SIZE: usize = 10;
+const QUICK_WARM_UP_TIME: Duration = Duration::from_millis(10);
+const QUICK_MEASUREMENT_TIME: Duration = Duration::from_millis(20);
+const QUICK_RESAMPLES: usize = 1000;
+// Time criterion spends on a case with few resamples and without plots
+const QUICK_REPORT_TIME: Duration = Duration::from_millis(5);
+// Largest input size of the quick mode. Past the caches of small machines,
+// while keeping every group of the quick mode within a minute
+const QUICK_MAX_BYTES: usize = 1 << 20;
+
+// Top level measurement organizers
+
+fn bench_data_structures<M: Measurement>(c: &mut Criterion<M>) {
+    compare_data_structures(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_clones<M: Measurement>(c: &mut Criterion<M>) {
+    compare_clones(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_construction<M: Measurement>(c: &mut Criterion<M>) {
+    compare_construction(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_drops<M: Measurement>(c: &mut Criterion<M>) {
+    compare_drops(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_traversal<M: Measurement>(c: &mut Criterion<M>) {
+    compare_traversal(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_f32_data_structures<M: Measurement>(c: &mut Criterion<M>) {
+    compare_f32_data_structures(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_i32_data_structures<M: Measurement>(c: &mut Criterion<M>) {
+    compare_int_data_structures::<i32, i32, _>(
+        "Sum of squares (i32)",
+        start_pow(),
+        end_pow(),
+        step_pow(),
+        c,
+    );
+}
+
+fn bench_i64_data_structures<M: Measurement>(c: &mut Criterion<M>) {
+    compare_int_data_structures::<i64, i64, _>(
+        "Sum of squares (i64)",
+        start_pow(),
+        end_pow(),
+        step_pow(),
+        c,
+    );
+}
+
+fn bench_stack_arrays<M: Measurement>(c: &mut Criterion<M>) {
+    compare_stack_arrays(c);
+}
+
+fn bench_compute_ceiling<M: Measurement>(c: &mut Criterion<M>) {
+    compare_compute_ceiling(c);
+}
+
+fn bench_maps<M: Measurement>(c: &mut Criterion<M>) {
+    compare_maps(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_indirection<M: Measurement>(c: &mut Criterion<M>) {
+    compare_indirection(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_node_sizes<M: Measurement>(c: &mut Criterion<M>) {
+    compare_node_sizes(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_slab_occupancy<M: Measurement>(c: &mut Criterion<M>) {
+    compare_slab_occupancy(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_shared_slices<M: Measurement>(c: &mut Criterion<M>) {
+    compare_shared_slices(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_deque_layouts<M: Measurement>(c: &mut Criterion<M>) {
+    compare_deque_layouts(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_ordered_sets<M: Measurement>(c: &mut Criterion<M>) {
+    compare_ordered_sets(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_row_lengths<M: Measurement>(c: &mut Criterion<M>) {
+    compare_row_lengths(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_padding<M: Measurement>(c: &mut Criterion<M>) {
+    compare_padding(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_alignment<M: Measurement>(c: &mut Criterion<M>) {
+    compare_alignment(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_record_layouts<M: Measurement>(c: &mut Criterion<M>) {
+    compare_record_layouts(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_mmap<M: Measurement>(c: &mut Criterion<M>) {
+    compare_mmap(start_pow(), mmap_end_pow(), step_pow(), c);
+}
+
+fn bench_hashers<M: Measurement>(c: &mut Criterion<M>) {
+    compare_hashers(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_hash_collisions<M: Measurement>(c: &mut Criterion<M>) {
+    compare_hash_collisions(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_load_factors<M: Measurement>(c: &mut Criterion<M>) {
+    compare_load_factors(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_slotmaps<M: Measurement>(c: &mut Criterion<M>) {
+    compare_slotmaps(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_quantized<M: Measurement>(c: &mut Criterion<M>) {
+    compare_quantized(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_mixed_precision<M: Measurement>(c: &mut Criterion<M>) {
+    compare_mixed_precision(start_pow(), end_pow(), step_pow(), c);
+}
+
+#[cfg(feature = "half")]
+fn bench_half_precision<M: Measurement>(c: &mut Criterion<M>) {
+    compare_half_precision(start_pow(), end_pow(), step_pow(), c);
+}
+
+#[cfg(feature = "fixed")]
+fn bench_fixed_point<M: Measurement>(c: &mut Criterion<M>) {
+    compare_fixed_point(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_complex<M: Measurement>(c: &mut Criterion<M>) {
+    compare_complex(start_pow(), end_pow(), step_pow(), c);
+}
+
+#[cfg(feature = "decimal")]
+fn bench_decimal<M: Measurement>(c: &mut Criterion<M>) {
+    compare_decimal(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_byte_input<M: Measurement>(c: &mut Criterion<M>) {
+    compare_byte_input(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_u8_data_structures<M: Measurement>(c: &mut Criterion<M>) {
+    compare_u8_data_structures(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_orders<M: Measurement>(c: &mut Criterion<M>) {
+    compare_orders(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_distributions<M: Measurement>(c: &mut Criterion<M>) {
+    compare_distributions(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_non_finite<M: Measurement>(c: &mut Criterion<M>) {
+    compare_non_finite(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_subnormals<M: Measurement>(c: &mut Criterion<M>) {
+    compare_subnormals(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_rayon_styles<M: Measurement>(c: &mut Criterion<M>) {
+    compare_rayon_styles(start_pow(), end_pow(), step_pow(), c);
+}
+
+#[cfg(target_arch = "x86_64")]
+fn bench_x86_intrinsics<M: Measurement>(c: &mut Criterion<M>) {
+    compare_x86_intrinsics(start_pow(), end_pow(), step_pow(), c);
+}
+
+#[cfg(target_arch = "aarch64")]
+fn bench_aarch64_intrinsics<M: Measurement>(c: &mut Criterion<M>) {
+    compare_aarch64_intrinsics(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_thread_counts<M: Measurement>(c: &mut Criterion<M>) {
+    compare_thread_counts(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_wrapper<M: Measurement>(c: &mut Criterion<M>) {
+    compare_wrapper(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_ordering_wrappers<M: Measurement>(c: &mut Criterion<M>) {
+    compare_ordering_wrappers(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_slices<M: Measurement>(c: &mut Criterion<M>) {
+    compare_slices(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_unrolling<M: Measurement>(c: &mut Criterion<M>) {
+    compare_unrolling(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_styles<M: Measurement>(c: &mut Criterion<M>) {
+    compare_styles(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_dispatch<M: Measurement>(c: &mut Criterion<M>) {
+    compare_dispatch(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_indexing<M: Measurement>(c: &mut Criterion<M>) {
+    compare_indexing(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn bench_block_sizes<M: Measurement>(c: &mut Criterion<M>) {
+    compare_block_sizes(start_pow(), end_pow(), step_pow(), c);
+}
+
+#[cfg(feature = "ndarray")]
+fn bench_ndarray<M: Measurement>(c: &mut Criterion<M>) {
+    compare_ndarray(start_pow(), end_pow(), step_pow(), c);
+}
+
+#[cfg(feature = "nalgebra")]
+fn bench_nalgebra<M: Measurement>(c: &mut Criterion<M>) {
+    compare_nalgebra(start_pow(), end_pow(), step_pow(), c);
+}
+
+#[cfg(feature = "arrow")]
+fn bench_arrow<M: Measurement>(c: &mut Criterion<M>) {
+    compare_arrow(start_pow(), end_pow(), step_pow(), c);
+}
+
+#[cfg(feature = "gpu")]
+fn bench_gpu<M: Measurement>(c: &mut Criterion<M>) {
+    compare_gpu(start_pow(), end_pow(), step_pow(), c);
+}
+
+fn compare_data_structures<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares");
+
+    // Iterate over data-sizes of powers of two from start_pow2 to end_pow2
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        // A 64-bit float is 8 bytes long, so we divide 1024 by 8 bytes to obtain the
+        // right data length
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        // Give input length to configure criterion
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        // Run all the benchmarks with this input size
+        bench_cases(
+            main_matrix_cases::<FloatOrd<f64>, _, _>(),
+            &input_bytes_human,
+            data_len,
+            &mut group,
+        );
+        // The SIMD kernels are written for f64 lanes only
+        #[cfg(feature = "simd")]
+        {
+            bench_iter_with(
+                "Vec (SIMD)",
+                &input_bytes_human,
+                &mut group,
+                || expect::<FloatOrd<f64>, Vec<_>, _>(create_distinct_data(data_len)),
+                |data| simd::sum_of_squares_simd(black_box(data)),
+            );
+            bench_iter_with(
+                "VecDeque (SIMD)",
+                &input_bytes_human,
+                &mut group,
+                || expect::<FloatOrd<f64>, VecDeque<_>, _>(create_distinct_data(data_len)),
+                |data| simd::sum_of_squares_simd_deque(black_box(data)),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+/// Run the main matrix with f32 elements accumulated in f32, to compare
+/// against the f64 matrix at the same input size in bytes. Twice as many
+/// elements as in the f64 matrix fit in the same bytes.
+fn compare_f32_data_structures<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    compare_cases(
+        "Sum of squares (f32)",
+        std::mem::size_of::<f32>(),
+        (start_pow2, end_pow2, step_pow2),
+        c,
+        main_matrix_cases::<FloatOrd<f32>, _, _>(),
+    );
+}
+
+/// Run the main matrix with integer elements, for comparing integer against
+/// floating point reduction throughput per container. The cases are named as in
+/// the main matrix.
+fn compare_int_data_structures<V, P, M>(
+    group_name: &str,
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) where
+    V: Int<P>,
+    P: IntPrimitive + Checked,
+    M: Measurement,
+    rand::distributions::Standard: rand::distributions::Distribution<P>,
+{
+    compare_cases(
+        group_name,
+        std::mem::size_of::<V>(),
+        (start_pow2, end_pow2, step_pow2),
+        c,
+        int_matrix_cases::<V, M, P>(),
+    );
+}
+
+// The compute ceiling runs the kernel over a 4 kB input, well within any L1d,
+// this many times per iteration
+const CEILING_BYTES: usize = 4 * 1024;
+const CEILING_REPEATS: usize = 1000;
+
+/// Measure the kernel on an input that stays in the L1d, repeated so that the
+/// loads are always hits and the arithmetic is the bottleneck. This is the
+/// ceiling the throughput of the larger inputs is compared against.
+fn compare_compute_ceiling<M: Measurement>(c: &mut Criterion<M>) {
+    let mut group = new_group(c, "Compute ceiling");
+
+    let data_len = CEILING_BYTES / std::mem::size_of::<f64>();
+    configure_for_size(
+        &mut group,
+        CEILING_BYTES * CEILING_REPEATS,
+        data_len * CEILING_REPEATS,
+    );
+    let parameter_name = format!(
+        "{} x {}",
+        human_readable_size(CEILING_BYTES),
+        CEILING_REPEATS
+    );
+
+    let data: LazyCell<Input<Vec<FloatOrd<f64>>>, _> = LazyCell::new(|| {
+        Input::with_sum(create_scrambled_data(data_len), |data| {
+            Expected::of(data).repeated(CEILING_REPEATS)
+        })
+    });
+
+    // The input goes through black_box on every repeat, so that the sums
+    // can't be hoisted out of the loop
+    bench_iter_with(
+        "Vec (by reference)",
+        &parameter_name,
+        &mut group,
+        || data.get(),
+        |data| {
+            (0..CEILING_REPEATS)
+                .map(|_| sum_of_squares_by_ref(black_box(*data)))
+                .sum::<f64>()
+        },
+    );
+    bench_iter_with(
+        "&[f64]",
+        &parameter_name,
+        &mut group,
+        || data.get().iter().map(|x| x.0).collect::<Vec<f64>>(),
+        |floats| {
+            (0..CEILING_REPEATS)
+                .map(|_| spp_experiments::sum_of_squares_slice_f64(black_box(floats)))
+                .sum::<f64>()
+        },
+    );
+
+    group.finish();
+}
+
+/// Compare stack-allocated `[V; N]` and `ArrayVec` against a heap-allocated
+/// `Vec` of the same length. The lengths are const generic parameters, so the
+/// sizes are listed explicitly, and stop at 64 kB to stay well within the
+/// stack.
+fn compare_stack_arrays<M: Measurement>(c: &mut Criterion<M>) {
+    let mut group = new_group(c, "Sum of squares (stack)");
+
+    // 1 kB, 4 kB, 16 kB and 64 kB of f64s
+    bench_stack_in_group::<FloatOrd<f64>, 128, _>(&mut group);
+    bench_stack_in_group::<FloatOrd<f64>, 512, _>(&mut group);
+    bench_stack_in_group::<FloatOrd<f64>, 2048, _>(&mut group);
+    bench_stack_in_group::<FloatOrd<f64>, 8192, _>(&mut group);
+
+    group.finish();
+}
+
+fn bench_stack_in_group<V, const N: usize, M>(group: &mut BenchmarkGroup<M>)
+where
+    V: Element<f64> + 'static,
+    M: Measurement,
+{
+    let input_size_bytes = N * std::mem::size_of::<f64>();
+    configure_for_size(group, input_size_bytes, N);
+    let input_bytes_human = human_readable_size(input_size_bytes);
+
+    let data: LazyCell<Input<Vec<V>>, _> = LazyCell::new(|| Input::of(create_scrambled_data(N)));
+
+    bench_iter_with(
+        "Vec",
+        &input_bytes_human,
+        group,
+        || data.get(),
+        |data| sum_of_squares_by_ref(black_box(*data)),
+    );
+    bench_iter_with(
+        "[_; N]",
+        &input_bytes_human,
+        group,
+        || -> [V; N] {
+            data.get()
+                .as_slice()
+                .try_into()
+                .expect("data length matches the array length")
+        },
+        |array| sum_of_squares_by_ref(black_box(array)),
+    );
+    bench_iter_with(
+        "ArrayVec",
+        &input_bytes_human,
+        group,
+        || data.get().iter().copied().collect::<ArrayVec<V, N>>(),
+        |array_vec| sum_of_squares_by_ref(black_box(array_vec)),
+    );
+}
+
+/// Compare summing over the values of keyed maps against a set of the values
+/// themselves. The maps are keyed by index, so each bucket also holds a key.
+/// All of them hold the same distinct values.
+fn compare_maps<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (maps)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        // Throughput counts the values only, not the keys
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        bench_iter_with(
+            "HashSet",
+            &input_bytes_human,
+            &mut group,
+            || expect::<FloatOrd<f64>, HashSet<_>, _>(create_distinct_data(data_len)),
+            |set| sum_of_squares_by_ref(black_box(set)),
+        );
+        bench_iter_with(
+            "HashMap (values)",
+            &input_bytes_human,
+            &mut group,
+            || {
+                let values = create_distinct_data::<FloatOrd<f64>, Vec<_>, _>(data_len);
+                expect_values(&values);
+                values.into_iter().enumerate().collect::<HashMap<_, _>>()
+            },
+            |map| sum_of_squares_by_move(black_box(map).values().copied()),
+        );
+        bench_iter_with(
+            "BTreeSet",
+            &input_bytes_human,
+            &mut group,
+            || expect::<FloatOrd<f64>, BTreeSet<_>, _>(create_distinct_data(data_len)),
+            |set| sum_of_squares_by_ref(black_box(set)),
+        );
+        bench_iter_with(
+            "BTreeMap (values)",
+            &input_bytes_human,
+            &mut group,
+            || {
+                let values = create_distinct_data::<FloatOrd<f64>, Vec<_>, _>(data_len);
+                expect_values(&values);
+                values.into_iter().enumerate().collect::<BTreeMap<_, _>>()
+            },
+            |map| sum_of_squares_by_move(black_box(map).values().copied()),
+        );
+    }
+
+    group.finish();
+}
+
+/// Separate the cost of an indirection per element from the cost of a
+/// non-contiguous spine: `Vec<Box<V>>` has a contiguous spine of pointers to
+/// separately allocated elements, compared against `Vec` and `LinkedList`.
+/// The boxes are allocated in sequence and tend to be laid out in sequence,
+/// so this measures the indirection rather than scatter. A linked list with
+/// its nodes allocated contiguously from an arena likewise separates pointer
+/// chasing from allocator scatter.
+fn compare_indirection<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (indirection)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        let data: LazyCell<Input<Vec<FloatOrd<f64>>>, _> =
+            LazyCell::new(|| Input::of(create_scrambled_data(data_len)));
+        bench_iter_with(
+            "Vec",
+            &input_bytes_human,
+            &mut group,
+            || data.get(),
+            |data| sum_of_squares_by_ref(black_box(*data)),
+        );
+        bench_iter_with(
+            "Vec<Box<_>>",
+            &input_bytes_human,
+            &mut group,
+            || data.get().iter().copied().map(Box::new).collect::<Vec<_>>(),
+            |boxed| sum_of_squares_by_move(black_box(boxed).iter().map(|x| **x)),
+        );
+        bench_iter_with(
+            "LinkedList",
+            &input_bytes_human,
+            &mut group,
+            || data.get().iter().copied().collect::<LinkedList<_>>(),
+            |list| sum_of_squares_by_ref(black_box(list)),
+        );
+        let arena = Arena::new();
+        bench_iter_with(
+            "LinkedList (arena)",
+            &input_bytes_human,
+            &mut group,
+            || ArenaList::from_iter_in(&arena, data.get().iter().copied()),
+            |list| sum_of_squares_by_ref(black_box(list)),
+        );
+    }
+
+    group.finish();
+}
+
+/// Sweep the node size of an unrolled linked list from one value per node,
+/// an ordinary linked list, to 64 values per node, tracing the spectrum from
+/// `LinkedList` to `Vec`.
+fn compare_node_sizes<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (unrolled list)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        // Node sizes are const generic parameters, so they are listed
+        // explicitly
+        bench_by_ref_in_group::<FloatOrd<f64>, UnrolledList<_, 1>, _, _>(
+            "UnrolledList<1>",
+            &input_bytes_human,
+            data_len,
+            &mut group,
+        );
+        bench_by_ref_in_group::<FloatOrd<f64>, UnrolledList<_, 2>, _, _>(
+            "UnrolledList<2>",
+            &input_bytes_human,
+            data_len,
+            &mut group,
+        );
+        bench_by_ref_in_group::<FloatOrd<f64>, UnrolledList<_, 4>, _, _>(
+            "UnrolledList<4>",
+            &input_bytes_human,
+            data_len,
+            &mut group,
+        );
+        bench_by_ref_in_group::<FloatOrd<f64>, UnrolledList<_, 8>, _, _>(
+            "UnrolledList<8>",
+            &input_bytes_human,
+            data_len,
+            &mut group,
+        );
+        bench_by_ref_in_group::<FloatOrd<f64>, UnrolledList<_, 16>, _, _>(
+            "UnrolledList<16>",
+            &input_bytes_human,
+            data_len,
+            &mut group,
+        );
+        bench_by_ref_in_group::<FloatOrd<f64>, UnrolledList<_, 32>, _, _>(
+            "UnrolledList<32>",
+            &input_bytes_human,
+            data_len,
+            &mut group,
+        );
+        bench_by_ref_in_group::<FloatOrd<f64>, UnrolledList<_, 64>, _, _>(
+            "UnrolledList<64>",
+            &input_bytes_human,
+            data_len,
+            &mut group,
+        );
+    }
+
+    group.finish();
+}
+
+// Fractions of vacant slots in the slab, spread evenly over the slab
+const SLAB_VACANT_FRACTIONS: [f64; 4] = [0.0, 0.25, 0.5, 0.75];
+
+/// Compare iteration over the occupied entries of a `Slab` with an increasing
+/// fraction of vacant slots against a `Vec`. Every case holds the same number
+/// of values; the holes come on top.
+fn compare_slab_occupancy<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (slab)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        let data: LazyCell<Input<Vec<FloatOrd<f64>>>, _> =
+            LazyCell::new(|| Input::of(create_scrambled_data(data_len)));
+        bench_iter_with(
+            "Vec",
+            &input_bytes_human,
+            &mut group,
+            || data.get(),
+            |data| sum_of_squares_by_ref(black_box(*data)),
+        );
+
+        for &vacant_fraction in SLAB_VACANT_FRACTIONS.iter() {
+            bench_iter_with(
+                &format!("Slab ({} % vacant)", vacant_fraction * 100.0),
+                &input_bytes_human,
+                &mut group,
+                || create_slab_with_vacancies(data.get(), vacant_fraction),
+                |slab| sum_of_squares_by_move(black_box(slab).iter().map(|(_, x)| *x)),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+/// Build a slab holding `values` with `vacant_fraction` of its slots vacant,
+/// spread evenly between the occupied ones.
+fn create_slab_with_vacancies<V: Copy>(values: &[V], vacant_fraction: f64) -> Slab<V> {
+    let slots = (values.len() as f64 / (1.0 - vacant_fraction)).round() as usize;
+    let mut slab = Slab::with_capacity(slots);
+    let mut values = values.iter().copied();
+
+    // Fill the holes with placeholders first and vacate them at the end, as a
+    // vacant slot would otherwise be reused by the next insert
+    let placeholder = values.clone().next().expect("slab needs values");
+    let mut holes = Vec::new();
+    for slot in 0..slots {
+        let is_hole =
+            ((slot + 1) as f64 * vacant_fraction).floor() > (slot as f64 * vacant_fraction).floor();
+        if is_hole {
+            holes.push(slab.insert(placeholder));
+        } else if let Some(value) = values.next() {
+            slab.insert(value);
+        }
+    }
+    // Rounding may leave a few values over
+    for value in values {
+        slab.insert(value);
+    }
+    for key in holes {
+        slab.remove(key);
+    }
+
+    slab
+}
+
+/// Compare the shared slices `Rc<[V]>` and `Arc<[V]>` against `Vec`. By
+/// value, the kernel holds the last reference, so the routine includes
+/// dropping the slice, as it includes dropping the `Vec`.
+fn compare_shared_slices<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (shared)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        let data: LazyCell<Input<Vec<FloatOrd<f64>>>, _> =
+            LazyCell::new(|| Input::of(create_scrambled_data(data_len)));
+
+        bench_iter_with(
+            "Vec (by reference)",
+            &input_bytes_human,
+            &mut group,
+            || data.get(),
+            |data| sum_of_squares_by_ref(black_box(*data)),
+        );
+        bench_iter_with(
+            "Rc<[_]> (by reference)",
+            &input_bytes_human,
+            &mut group,
+            || Rc::<[_]>::from(data.get().as_slice()),
+            |rc| sum_of_squares_by_ref(black_box(&**rc)),
+        );
+        bench_iter_with(
+            "Arc<[_]> (by reference)",
+            &input_bytes_human,
+            &mut group,
+            || Arc::<[_]>::from(data.get().as_slice()),
+            |arc| sum_of_squares_by_ref(black_box(&**arc)),
+        );
+
+        bench_batched_with(
+            "Vec (by value)",
+            &input_bytes_human,
+            &mut group,
+            || data.get(),
+            |data| (*data).clone(),
+            |data| sum_of_squares_by_move(black_box(data)),
+        );
+        bench_batched_with(
+            "Rc<[_]> (by value)",
+            &input_bytes_human,
+            &mut group,
+            || data.get(),
+            |data| Rc::<[_]>::from(data.as_slice()),
+            |rc| {
+                let rc = black_box(rc);
+                let sum = sum_of_squares_by_ref(&*rc);
+                drop(rc);
+                sum
+            },
+        );
+        bench_batched_with(
+            "Arc<[_]> (by value)",
+            &input_bytes_human,
+            &mut group,
+            || data.get(),
+            |data| Arc::<[_]>::from(data.as_slice()),
+            |arc| {
+                let arc = black_box(arc);
+                let sum = sum_of_squares_by_ref(&*arc);
+                drop(arc);
+                sum
+            },
+        );
+    }
+
+    group.finish();
+}
+
+/// Compare a `VecDeque` whose ring buffer is contiguous against one split in
+/// half at the wrap point, and against the split one after `make_contiguous`.
+/// The data is not cloned per iteration, as a clone doesn't preserve the
+/// layout.
+fn compare_deque_layouts<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (VecDeque layout)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        let data: LazyCell<Input<Vec<FloatOrd<f64>>>, _> =
+            LazyCell::new(|| Input::of(create_scrambled_data(data_len)));
+
+        bench_iter_with(
+            "VecDeque (contiguous)",
+            &input_bytes_human,
+            &mut group,
+            || data.get().iter().copied().collect::<VecDeque<_>>(),
+            |contiguous| sum_of_squares_by_ref(black_box(contiguous)),
+        );
+        bench_iter_with(
+            "VecDeque (split)",
+            &input_bytes_human,
+            &mut group,
+            || create_split_deque(data.get()),
+            |split| sum_of_squares_by_ref(black_box(split)),
+        );
+        bench_iter_with(
+            "VecDeque (make_contiguous)",
+            &input_bytes_human,
+            &mut group,
+            || {
+                let mut made_contiguous = create_split_deque(data.get());
+                made_contiguous.make_contiguous();
+                made_contiguous
+            },
+            |made_contiguous| sum_of_squares_by_ref(black_box(made_contiguous)),
+        );
+    }
+
+    group.finish();
+}
+
+/// Build a deque of `values` whose ring buffer wraps around in the middle, so
+/// that each half is in a separate slice.
+fn create_split_deque<V: Copy>(values: &[V]) -> VecDeque<V> {
+    let mut deque = VecDeque::with_capacity(values.len());
+
+    // Advance the empty ring buffer so that the second half of the values
+    // wraps around to the start of the allocation
+    if let Some(&first) = values.first() {
+        for _ in 0..deque.capacity() - values.len() / 2 {
+            deque.push_back(first);
+            deque.pop_front();
+        }
+    }
+    deque.extend(values.iter().copied());
+
+    let (front, back) = deque.as_slices();
+    assert_eq!(
+        (front.len(), back.len()),
+        (values.len() / 2, values.len() - values.len() / 2)
+    );
+
+    deque
+}
+
+/// Compare two ordered, pointer-based sets: the B-tree of `BTreeSet` and the
+/// skip list of crossbeam's `SkipSet`. The skip list's iterator yields entries
+/// rather than references, so its values are copied out of the entries.
+fn compare_ordered_sets<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (ordered sets)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        let data: LazyCell<Input<Vec<FloatOrd<f64>>>, _> =
+            LazyCell::new(|| Input::of(create_distinct_data(data_len)));
+
+        bench_iter_with(
+            "BTreeSet",
+            &input_bytes_human,
+            &mut group,
+            || data.get().iter().copied().collect::<BTreeSet<_>>(),
+            |set| sum_of_squares_by_ref(black_box(set)),
+        );
+        bench_iter_with(
+            "SkipSet",
+            &input_bytes_human,
+            &mut group,
+            || data.get().iter().copied().collect::<SkipSet<_>>(),
+            |set| sum_of_squares_by_move(black_box(set).iter().map(|entry| *entry.value())),
+        );
+    }
+
+    group.finish();
+}
+
+// Row lengths of the nested vectors in elements, 8 bytes to 8 kB per row
+const ROW_LENS: [usize; 6] = [1, 4, 16, 64, 256, 1024];
+
+/// Sweep the row length of a nested `Vec<Vec<V>>` at a fixed total size. Short
+/// rows mean one heap hop per few values, long rows approach a contiguous
+/// `Vec`.
+fn compare_row_lengths<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (nested)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        let data: LazyCell<Input<Vec<FloatOrd<f64>>>, _> =
+            LazyCell::new(|| Input::of(create_scrambled_data(data_len)));
+        bench_iter_with(
+            "Vec",
+            &input_bytes_human,
+            &mut group,
+            || data.get(),
+            |data| sum_of_squares_by_ref(black_box(*data)),
+        );
+
+        // Rows longer than the input are the same as a single row
+        for &row_len in ROW_LENS.iter().filter(|&&row_len| row_len <= data_len) {
+            bench_iter_with(
+                &format!("Vec<Vec<_>> (row {})", row_len),
+                &input_bytes_human,
+                &mut group,
+                || {
+                    data.get()
+                        .chunks(row_len)
+                        .map(<[_]>::to_vec)
+                        .collect::<Vec<Vec<_>>>()
+                },
+                |rows| {
+                    black_box(rows)
+                        .iter()
+                        .map(sum_of_squares_by_ref)
+                        .sum::<f64>()
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+/// Compare buffers starting at a cache line and at a page boundary, and 8 bytes
+/// past each, so that the misaligned buffers straddle one more cache line.
+fn compare_alignment<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (alignment)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        let data: LazyCell<Input<Vec<f64>>, _> =
+            LazyCell::new(|| Input::of(create_scrambled_data(data_len)));
+
+        for &(align, align_name) in [(aligned::CACHE_LINE, "64 B"), (aligned::PAGE, "4 kB")].iter()
+        {
+            for &offset in [0, std::mem::size_of::<f64>()].iter() {
+                let ds_name = if offset == 0 {
+                    format!("{} aligned", align_name)
+                } else {
+                    format!("{} aligned + {} B", align_name, offset)
+                };
+                bench_iter_with(
+                    &ds_name,
+                    &input_bytes_human,
+                    &mut group,
+                    || aligned::AlignedData::new(data.get(), align, offset),
+                    |buffer| {
+                        spp_experiments::sum_of_squares_slice_f64(black_box(buffer.as_slice()))
+                    },
+                );
+            }
+        }
+    }
+
+    group.finish();
+}
+
+/// Compare `Vec`s of values padded to 8, 16, 32 and 64 bytes, with the generic
+/// by-reference kernel. Every case sums the same number of values; the input
+/// size is that of the values alone, so the padded inputs take 2x, 4x and 8x of
+/// it in memory.
+fn compare_padding<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (padding)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        // Padding lengths are const generic parameters, so they are listed
+        // explicitly
+        bench_padded_in_group::<0, _>(&input_bytes_human, data_len, &mut group);
+        bench_padded_in_group::<8, _>(&input_bytes_human, data_len, &mut group);
+        bench_padded_in_group::<24, _>(&input_bytes_human, data_len, &mut group);
+        bench_padded_in_group::<56, _>(&input_bytes_human, data_len, &mut group);
+    }
+
+    group.finish();
+}
+
+fn bench_padded_in_group<const N: usize, M>(
+    parameter_name: &str,
+    data_len: usize,
+    group: &mut BenchmarkGroup<M>,
+) where
+    M: Measurement,
+{
+    let ds_name = format!(
+        "Vec ({} B elements)",
+        std::mem::size_of::<layout::Padded<N>>()
+    );
+    // The input size of the group, and so the throughput, counts the values
+    // alone, but the memory check needs the padding too
+    let padded_size_bytes = data_len * std::mem::size_of::<layout::Padded<N>>();
+    if !admit_input(&ds_name, parameter_name, padded_size_bytes, data_len, 1) {
+        return;
+    }
+
+    let data: Vec<layout::Padded<N>> = expect(create_scrambled_data(data_len));
+    bench_iter(&ds_name, parameter_name, group, || {
+        sum_of_squares_by_ref(black_box(&data))
+    });
+}
+
+/// Compare summing one field of multi-field records stored as an array of
+/// structs against a struct of arrays, for 1 to 8 fields per record. The input
+/// size counts the summed field only, so the array of structs reads `N` times
+/// as many bytes.
+fn compare_record_layouts<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (record layout)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        // Field counts are const generic parameters, so they are listed
+        // explicitly
+        bench_layouts_in_group::<1, _>(&input_bytes_human, data_len, &mut group);
+        bench_layouts_in_group::<2, _>(&input_bytes_human, data_len, &mut group);
+        bench_layouts_in_group::<4, _>(&input_bytes_human, data_len, &mut group);
+        bench_layouts_in_group::<8, _>(&input_bytes_human, data_len, &mut group);
+    }
+
+    group.finish();
+}
+
+fn bench_layouts_in_group<const N: usize, M>(
+    parameter_name: &str,
+    data_len: usize,
+    group: &mut BenchmarkGroup<M>,
+) where
+    M: Measurement,
+{
+    // Both layouts hold every field of every record
+    let input_size_bytes = N * data_len * std::mem::size_of::<f64>();
+    let records = LazyCell::new(|| {
+        // The kernels sum the first field
+        Input::with_sum(layout::create_scrambled_records::<N>(data_len), |records| {
+            Expected::of(records.iter().map(|record| &record.fields[0]))
+        })
+    });
+
+    let ds_name = format!("AoS ({} fields)", N);
+    if admit_input(&ds_name, parameter_name, input_size_bytes, data_len, 1) {
+        let records = records.get();
+        bench_iter(&ds_name, parameter_name, group, || {
+            layout::sum_of_squares_aos(black_box(records))
+        });
+    }
+    let ds_name = format!("SoA ({} fields)", N);
+    if admit_input(&ds_name, parameter_name, input_size_bytes, data_len, 2) {
+        let columns = layout::Columns::from_records(records.get());
+        bench_iter(&ds_name, parameter_name, group, || {
+            layout::sum_of_squares_soa(black_box(&columns))
+        });
+    }
+}
+
+// The memory-mapped inputs extend the sweep to 1 GB, past the cache sizes and
+// into page cache territory, unless the end of the sweep is overridden
+const MMAP_END_POW: u32 = 30;
+
+fn mmap_end_pow() -> u32 {
+    sweep::end_pow_or(experiment(), MMAP_END_POW).unwrap_or_else(|e| {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    })
+}
+
+/// Compare a memory-mapped file against a `Vec` of the same values. The warm
+/// mapping is mapped once and stays resident; the cold one is evicted from the
+/// page cache and mapped afresh before every iteration, so each iteration
+/// faults the pages back in from disk.
+fn compare_mmap<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (mmap)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        let data: LazyCell<Input<Vec<f64>>, _> =
+            LazyCell::new(|| Input::of(create_scrambled_data(data_len)));
+        let path = mmap::data_path(input_size_bytes);
+        // Written for the first mapped case to run
+        let file = LazyCell::new(|| {
+            mmap::write_data_file(&path, data.get()).expect("could not write the data file")
+        });
+
+        bench_iter_with(
+            "Vec<f64>",
+            &input_bytes_human,
+            &mut group,
+            || data.get(),
+            |data| spp_experiments::sum_of_squares_slice_f64(black_box(data)),
+        );
+
+        // The dry run doesn't write the data file, so the mapped cases are
+        // modelled rather than probed
+        if DRY_RUN.load(Ordering::Relaxed) {
+            for &ds_name in ["mmap (warm)", "mmap (cold)"].iter() {
+                if selected(ds_name, &input_bytes_human) {
+                    record_listed(ds_name, &input_bytes_human, input_size_bytes);
+                }
+            }
+            continue;
+        }
+
+        bench_iter_with(
+            "mmap (warm)",
+            &input_bytes_human,
+            &mut group,
+            || {
+                LazyCell::force(&file);
+                // The mapping holds the values of the input
+                data.get();
+                let warm = mmap::MappedData::open(&path).expect("could not map the data file");
+                // Fault every page in before measuring
+                black_box(spp_experiments::sum_of_squares_slice_f64(warm.as_slice()));
+                warm
+            },
+            |warm| spp_experiments::sum_of_squares_slice_f64(black_box(warm.as_slice())),
+        );
+
+        if !admit("mmap (cold)", &input_bytes_human, 1) {
+            continue;
+        }
+        LazyCell::force(&file);
+        if let Err(e) = mmap::evict_from_page_cache(&path) {
+            eprintln!("warning: skipping the cold mmap benchmarks: {}", e);
+            continue;
+        }
+        data.get();
+        // The mapping is returned so that it is unmapped outside the timed
+        // region. Each iteration maps the file right after evicting it, as a
+        // batch of mappings would be evicted before the first of them is read.
+        bench_batched_by(
+            "mmap (cold)",
+            &input_bytes_human,
+            &mut group,
+            BatchSize::PerIteration,
+            || {
+                mmap::evict_from_page_cache(&path).expect("could not evict the data file");
+                mmap::MappedData::open(&path).expect("could not map the data file")
+            },
+            |mapped| {
+                let sum = spp_experiments::sum_of_squares_slice_f64(black_box(mapped.as_slice()));
+                (sum, mapped)
+            },
+        );
+    }
+
+    group.finish();
+    if let Err(e) = mmap::remove_data_files() {
+        eprintln!("warning: could not remove the mmap data files: {}", e);
+    }
+}
+
+/// Compare `HashSet` iteration with different hashers. The hasher decides
+/// where each value lands in the table, and so the order in which iteration
+/// visits them.
+fn compare_hashers<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (hashers)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        bench_by_ref_in_group::<FloatOrd<f64>, HashSet<_, RandomState>, _, _>(
+            "HashSet (SipHash)",
+            &input_bytes_human,
+            data_len,
+            &mut group,
+        );
+        bench_by_ref_in_group::<FloatOrd<f64>, HashSet<_, ahash::RandomState>, _, _>(
+            "HashSet (aHash)",
+            &input_bytes_human,
+            data_len,
+            &mut group,
+        );
+        bench_by_ref_in_group::<FloatOrd<f64>, HashSet<_, fxhash::FxBuildHasher>, _, _>(
+            "HashSet (FxHash)",
+            &input_bytes_human,
+            data_len,
+            &mut group,
+        );
+        bench_by_ref_in_group::<FloatOrd<f64>, HashSet<_, fnv::FnvBuildHasher>, _, _>(
+            "HashSet (FNV)",
+            &input_bytes_human,
+            data_len,
+            &mut group,
+        );
+    }
+
+    group.finish();
+}
+
+/// Compare `HashSet` iteration and construction over values whose hashes
+/// collide against distinct random values, both with FxHash, the unkeyed
+/// hasher the colliding values are crafted for, see
+/// [`data::create_colliding_data`].
+///
+/// Iteration scans the buckets regardless of where the values landed, while
+/// construction probes past every value inserted into the same few buckets
+/// before, which bounds the worst case of the hashed containers.
+fn compare_hash_collisions<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (hash collisions)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        for &colliding in [false, true].iter() {
+            let input_name = if colliding { "colliding" } else { "scrambled" };
+            let ds_name = format!("HashSet ({})", input_name);
+            let collect_name = format!("HashSet (collect {})", input_name);
+            let values: LazyCell<Vec<FloatOrd<f64>>, _> = LazyCell::new(|| {
+                if colliding {
+                    data::create_colliding_data(data_len)
+                } else {
+                    create_distinct_data(data_len)
+                }
+            });
+
+            // The values and the set built from them
+            if admit(&collect_name, &input_bytes_human, 2) {
+                let values = &*values;
+                bench_batched(
+                    &collect_name,
+                    &input_bytes_human,
+                    &mut group,
+                    || (),
+                    |()| {
+                        NoSum(
+                            values
+                                .iter()
+                                .copied()
+                                .collect::<HashSet<_, fxhash::FxBuildHasher>>(),
+                        )
+                    },
+                );
+            }
+            bench_by_ref_with_input::<_, HashSet<_, fxhash::FxBuildHasher>, _, _>(
+                &ds_name,
+                &input_bytes_human,
+                || values.to_vec(),
+                &mut group,
+            );
+        }
+    }
+
+    group.finish();
+}
+
+// Nominal load factors of the hash tables, up to the maximum of 7/8
+const LOAD_FACTORS: [f64; 3] = [0.25, 0.5, 0.875];
+
+/// Compare `HashSet` iteration at different table load factors. Iteration
+/// scans every bucket, so a sparser table reads more memory per value.
+///
+/// The table has a power-of-two number of buckets, so each size is the bucket
+/// count at 8 bytes per bucket, and the table holds that many buckets times the
+/// load factor of values. Sizes that aren't powers of two are skipped. The
+/// actual load factor is recorded per benchmark in the metadata.
+fn compare_load_factors<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (load factor)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        if !input_size_bytes.is_power_of_two() {
+            continue;
+        }
+        let buckets = input_size_bytes / std::mem::size_of::<f64>();
+        let input_bytes_human = human_readable_size(input_size_bytes);
+        // The table allocates 8/7 buckets per unit of capacity, rounded up to
+        // a power of two, so this is exactly `buckets`
+        let capacity = buckets * 7 / 8;
+        let data: LazyCell<Vec<FloatOrd<f64>>, _> =
+            LazyCell::new(|| create_distinct_data(capacity));
+
+        for &load_factor in LOAD_FACTORS.iter() {
+            let ds_name = format!("HashSet ({} % load)", load_factor * 100.0);
+            // The values and the table, which has `buckets` buckets whatever
+            // the load
+            if !admit_input(&ds_name, &input_bytes_human, input_size_bytes, buckets, 2) {
+                continue;
+            }
+            let data_len = (buckets as f64 * load_factor) as usize;
+            configure_for_size(&mut group, data_len * std::mem::size_of::<f64>(), data_len);
+            expect_values(&data[..data_len]);
+
+            let mut set = HashSet::with_capacity(capacity);
+            set.extend(data[..data_len].iter().copied());
+
+            if !DRY_RUN.load(Ordering::Relaxed) {
+                let buckets = set.capacity() as f64 * 8.0 / 7.0;
+                metadata::record(
+                    &case_id(&ds_name, &input_bytes_human),
+                    "load_factor",
+                    set.len() as f64 / buckets,
+                );
+            }
+
+            bench_by_ref_with_data(&ds_name, &input_bytes_human, set, &mut group);
+        }
+    }
+
+    group.finish();
+}
+
+/// Compare a densely filled `SlotMap` against a fragmented one, with every
+/// other slot vacant.
+fn compare_slotmaps<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (slotmap)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        let data: LazyCell<Input<Vec<FloatOrd<f64>>>, _> =
+            LazyCell::new(|| Input::of(create_scrambled_data(data_len)));
+        bench_iter_with(
+            "Vec",
+            &input_bytes_human,
+            &mut group,
+            || data.get(),
+            |data| sum_of_squares_by_ref(black_box(*data)),
+        );
+
+        bench_iter_with(
+            "SlotMap",
+            &input_bytes_human,
+            &mut group,
+            || {
+                let mut map = SlotMap::with_capacity(data_len);
+                for &value in data.get().iter() {
+                    map.insert(value);
+                }
+                map
+            },
+            |map| sum_of_squares_by_move(black_box(map).values().copied()),
+        );
+
+        bench_iter_with(
+            "SlotMap (fragmented)",
+            &input_bytes_human,
+            &mut group,
+            || {
+                // Interleave a placeholder with each value and remove the
+                // placeholders once all values are in, as a vacant slot would
+                // otherwise be reused by the next insert
+                let mut map = SlotMap::with_capacity(2 * data_len);
+                let placeholders = data
+                    .get()
+                    .iter()
+                    .map(|&value| {
+                        let placeholder = map.insert(value);
+                        map.insert(value);
+                        placeholder
+                    })
+                    .collect::<Vec<_>>();
+                for placeholder in placeholders {
+                    map.remove(placeholder);
+                }
+                map
+            },
+            |map| sum_of_squares_by_move(black_box(map).values().copied()),
+        );
+    }
+
+    group.finish();
+}
+
+/// Compare plain `f64`s against values quantized to `u32` and `u16` and
+/// decoded during the reduction. The input size is that of the `f64`s, so the
+/// quantized inputs are a half and a quarter of it in memory.
+fn compare_quantized<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (quantized)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        let data: LazyCell<Input<Vec<f64>>, _> =
+            LazyCell::new(|| Input::of(create_scrambled_data(data_len)));
+
+        bench_iter_with(
+            "Vec<f64>",
+            &input_bytes_human,
+            &mut group,
+            || data.get(),
+            |data| spp_experiments::sum_of_squares_slice_f64(black_box(data)),
+        );
+        bench_iter_with(
+            "Quantized<u32>",
+            &input_bytes_human,
+            &mut group,
+            || {
+                // Taken first, as it records the sum of the values unquantized
+                let data = data.get();
+                expect_quantized::<u32>(data);
+                quantized::Quantized::<u32>::new(data)
+            },
+            |quantized| quantized::sum_of_squares_quantized(black_box(quantized)),
+        );
+        bench_iter_with(
+            "Quantized<u16>",
+            &input_bytes_human,
+            &mut group,
+            || {
+                // Taken first, as it records the sum of the values unquantized
+                let data = data.get();
+                expect_quantized::<u16>(data);
+                quantized::Quantized::<u16>::new(data)
+            },
+            |quantized| quantized::sum_of_squares_quantized(black_box(quantized)),
+        );
+    }
+
+    group.finish();
+}
+
+/// Record the expected sum of `values` quantized to `Q`, if sums are verified.
+/// A decoded value is off by up to half a quantum, `(max - min) / Q::MAX`, so
+/// its square is off by up to `|x| * quantum + quantum² / 4`.
+fn expect_quantized<Q: quantized::Quantum>(values: &[f64]) {
+    if !verify_sums() {
+        return;
+    }
+
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let quantum = if max > min { (max - min) / Q::MAX } else { 0.0 };
+    let error = values
+        .iter()
+        .map(|x| x.abs() * quantum + quantum * quantum / 4.0)
+        .sum::<f64>();
+    let sum = checksum::expected_sum::<f64, f64>(values);
+    expect_sum(Expected::Float {
+        sum,
+        tolerance: error / sum + checksum::tolerance::<f64>(values.len()),
+    });
+}
+
+/// Compare `f32` storage with `f64` accumulation against pure `f32` and pure
+/// `f64`. Every case sums the same number of values; the input size is that of
+/// the `f64`s, so the `f32` inputs take half of it in memory.
+fn compare_mixed_precision<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (mixed precision)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        let data: LazyCell<Input<Vec<f64>>, _> =
+            LazyCell::new(|| Input::of(create_scrambled_data(data_len)));
+        let singles: LazyCell<Input<Vec<f32>>, _> =
+            LazyCell::new(|| Input::of(data.get().iter().map(|&x| x as f32).collect()));
+
+        bench_iter_with(
+            "f64",
+            &input_bytes_human,
+            &mut group,
+            || data.get(),
+            |data| spp_experiments::sum_of_squares_slice_f64(black_box(data)),
+        );
+        bench_iter_with(
+            "f32",
+            &input_bytes_human,
+            &mut group,
+            || singles.get(),
+            |singles| spp_experiments::sum_of_squares_slice_f32(black_box(singles)),
+        );
+        bench_iter_with(
+            "f32 (f64 accumulator)",
+            &input_bytes_human,
+            &mut group,
+            || singles.get(),
+            |singles| spp_experiments::sum_of_squares_slice_f32_widening(black_box(singles)),
+        );
+    }
+
+    group.finish();
+}
+
+/// Run the containers of the main matrix on Q32.32 fixed-point elements. A
+/// Q32.32 value is 8 bytes like an `f64`, so the lengths match the main matrix
+/// and the cases are named as in it.
+#[cfg(feature = "fixed")]
+fn compare_fixed_point<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    use fixed_point::Q32;
+
+    compare_cases(
+        "Sum of squares (fixed-point)",
+        std::mem::size_of::<Q32>(),
+        (start_pow2, end_pow2, step_pow2),
+        c,
+        container_cases!("by reference", bench_fixed_by_ref_in_group, Q32, [], [M]),
+    );
+}
+
+#[cfg(feature = "fixed")]
+fn bench_fixed_by_ref_in_group<T, M>(
+    ds_name: &str,
+    parameter_name: &str,
+    data_len: usize,
+    group: &mut BenchmarkGroup<M>,
+) where
+    T: iter::FromIterator<fixed_point::Q32>,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a fixed_point::Q32>,
+    M: Measurement,
+{
+    if !admit(ds_name, parameter_name, 1) {
+        return;
+    }
+
+    let values: Vec<fixed_point::Q32> = fixed_point::create_scrambled_fixed(data_len);
+    let data = values.iter().copied().collect::<T>();
+    check_len(ds_name, parameter_name, &data, data_len);
+    if verify_sums() {
+        // Each square is truncated to the 32 fractional bits
+        let values = values.iter().map(|x| x.to_num::<f64>()).collect::<Vec<_>>();
+        let expected = checksum::expected_sum(&values);
+        expect_sum(Expected::Float {
+            sum: expected,
+            tolerance: values.len() as f64 * 2f64.powi(-32) / expected
+                + checksum::tolerance::<f64>(values.len()),
+        });
+    }
+
+    verify_residency(ds_name, parameter_name, &data);
+    bench_iter(ds_name, parameter_name, group, move || {
+        fixed_point::sum_of_squares_fixed_by_ref(black_box(&data))
+    });
+}
+
+/// Run the containers of the main matrix on `Decimal` elements. A `Decimal` is
+/// 16 bytes, so each input holds half as many values as in the main matrix.
+/// The cases are named as in it.
+#[cfg(feature = "decimal")]
+fn compare_decimal<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    compare_cases(
+        "Sum of squares (decimal)",
+        std::mem::size_of::<Decimal>(),
+        (start_pow2, end_pow2, step_pow2),
+        c,
+        container_cases!(
+            "by reference",
+            bench_decimal_by_ref_in_group,
+            Decimal,
+            [],
+            [M]
+        ),
+    );
+}
+
+#[cfg(feature = "decimal")]
+fn bench_decimal_by_ref_in_group<T, M>(
+    ds_name: &str,
+    parameter_name: &str,
+    data_len: usize,
+    group: &mut BenchmarkGroup<M>,
+) where
+    T: iter::FromIterator<Decimal>,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a Decimal>,
+    M: Measurement,
+{
+    if !admit(ds_name, parameter_name, 1) {
+        return;
+    }
+
+    let values: Vec<Decimal> = decimal::create_scrambled_decimals(data_len);
+    let data = values.iter().copied().collect::<T>();
+    check_len(ds_name, parameter_name, &data, data_len);
+    if verify_sums() {
+        // The squares and sums of the generated values are exact
+        let expected = values.iter().fold(Decimal::ZERO, |sum, &x| sum + x * x);
+        expect_sum(Expected::Float {
+            sum: rust_decimal::prelude::ToPrimitive::to_f64(&expected).unwrap_or(f64::NAN),
+            tolerance: f64::EPSILON,
+        });
+    }
+
+    verify_residency(ds_name, parameter_name, &data);
+    bench_iter(ds_name, parameter_name, group, move || {
+        decimal::sum_of_squares_decimal_by_ref(black_box(&data))
+    });
+}
+
+/// Compare `half::f16` storage, widened to `f32` or `f64` for accumulation,
+/// against `f32` and `f64` storage. Unlike the mixed precision group, every case
+/// fills the same input size, so the `f16` inputs hold 4x as many values as the
+/// `f64` inputs and should cross each cache level at a 4x larger length.
+#[cfg(feature = "half")]
+fn compare_half_precision<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (f16)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        let data_len = input_size_bytes / std::mem::size_of::<half::f16>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let halves = LazyCell::new(|| {
+            let halves = create_scrambled_data::<FloatOrd<f64>, Vec<_>, _>(data_len)
+                .into_iter()
+                .map(|x| half::f16::from_f64(x.0))
+                .collect::<Vec<half::f16>>();
+            // Widening is exact, and so are the squares in f32
+            Input::with_sum(halves, |halves| {
+                Expected::of::<f32, f32>(&halves.iter().map(|x| x.to_f32()).collect::<Vec<_>>())
+            })
+        });
+        bench_iter_with(
+            "f16 (f32 accumulator)",
+            &input_bytes_human,
+            &mut group,
+            || halves.get(),
+            |halves| spp_experiments::sum_of_squares_slice_f16_widening_f32(black_box(halves)),
+        );
+        bench_iter_with(
+            "f16 (f64 accumulator)",
+            &input_bytes_human,
+            &mut group,
+            || halves.get(),
+            |halves| spp_experiments::sum_of_squares_slice_f16_widening(black_box(halves)),
+        );
+        drop(halves);
+
+        let data_len = input_size_bytes / std::mem::size_of::<f32>();
+        set_throughput(&mut group, input_size_bytes, data_len);
+        bench_iter_with(
+            "f32",
+            &input_bytes_human,
+            &mut group,
+            || {
+                expect::<_, Vec<f32>, _>(
+                    create_scrambled_data::<FloatOrd<f32>, Vec<_>, _>(data_len)
+                        .into_iter()
+                        .map(|x| x.0)
+                        .collect(),
+                )
+            },
+            |singles| spp_experiments::sum_of_squares_slice_f32(black_box(singles)),
+        );
+
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        set_throughput(&mut group, input_size_bytes, data_len);
+        bench_iter_with(
+            "f64",
+            &input_bytes_human,
+            &mut group,
+            || expect::<_, Vec<f64>, _>(create_scrambled_data(data_len)),
+            |data| spp_experiments::sum_of_squares_slice_f64(black_box(data)),
+        );
+    }
+
+    group.finish();
+}
+
+/// Compare summing `|z|²` over `Complex<f64>` elements in the contiguous
+/// containers against `f64` elements of the same input size, which hold twice as
+/// many values.
+fn compare_complex<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (complex)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<Complex<f64>>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        bench_complex_by_ref_in_group::<Vec<_>, _>(
+            "Vec<Complex>",
+            &input_bytes_human,
+            data_len,
+            &mut group,
+        );
+        bench_complex_by_ref_in_group::<Box<[_]>, _>(
+            "Box<[Complex]>",
+            &input_bytes_human,
+            data_len,
+            &mut group,
+        );
+        bench_complex_by_ref_in_group::<SmallVec<[_; 1024]>, _>(
+            "SmallVec<[Complex; 1024]>",
+            &input_bytes_human,
+            data_len,
+            &mut group,
+        );
+        bench_complex_by_ref_in_group::<VecDeque<_>, _>(
+            "VecDeque<Complex>",
+            &input_bytes_human,
+            data_len,
+            &mut group,
+        );
+
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        set_throughput(&mut group, input_size_bytes, data_len);
+        bench_by_ref_in_group::<FloatOrd<f64>, Vec<_>, _, _>(
+            "Vec<f64>",
+            &input_bytes_human,
+            data_len,
+            &mut group,
+        );
+    }
+
+    group.finish();
+}
+
+fn bench_complex_by_ref_in_group<T, M>(
+    ds_name: &str,
+    parameter_name: &str,
+    data_len: usize,
+    group: &mut BenchmarkGroup<M>,
+) where
+    T: iter::FromIterator<Complex<f64>>,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a Complex<f64>>,
+    M: Measurement,
+{
+    if !admit(ds_name, parameter_name, 1) {
+        return;
+    }
+
+    let values: Vec<Complex<f64>> = create_scrambled_complex(data_len);
+    let data = values.iter().copied().collect::<T>();
+    check_len(ds_name, parameter_name, &data, data_len);
+    if verify_sums() {
+        // |z|² sums the squares of both parts
+        let parts = values
+            .iter()
+            .flat_map(|z| [z.re, z.im])
+            .collect::<Vec<f64>>();
+        expect_values::<f64, f64>(&parts);
+    }
+
+    verify_residency(ds_name, parameter_name, &data);
+    bench_iter(ds_name, parameter_name, group, move || {
+        spp_experiments::sum_of_squares_complex_by_ref(black_box(&data))
+    });
+}
+
+/// Compare reducing `f64`s that arrive as raw bytes, reinterpreted in place
+/// with bytemuck, against first copying them into a `Vec<f64>`.
+fn compare_byte_input<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (bytes)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        let bytes = LazyCell::new(|| {
+            let values: Vec<FloatOrd<f64>> = create_scrambled_data(data_len);
+            let bytes = values
+                .iter()
+                .flat_map(|x| x.0.to_ne_bytes())
+                .collect::<Vec<u8>>();
+            Input::with_sum(bytes, |_| Expected::of(&values))
+        });
+        if admit("cast (zero-copy)", &input_bytes_human, 1) {
+            // A Vec<u8> is only guaranteed to be byte-aligned, but the
+            // allocator aligns allocations this large for any primitive
+            let bytes = bytes.get();
+            if bytemuck::try_cast_slice::<u8, f64>(bytes).is_err() {
+                eprintln!("warning: skipping the zero-copy benchmark, input is misaligned");
+            } else {
+                bench_iter("cast (zero-copy)", &input_bytes_human, &mut group, || {
+                    let values = bytemuck::cast_slice::<u8, f64>(black_box(bytes));
+                    spp_experiments::sum_of_squares_slice_f64(values)
+                });
+            }
+        }
+        // The kernel copies the input
+        if admit("copy", &input_bytes_human, 2) {
+            let bytes = bytes.get();
+            bench_iter("copy", &input_bytes_human, &mut group, || {
+                let values = black_box(bytes)
+                    .chunks_exact(std::mem::size_of::<f64>())
+                    .map(|b| f64::from_ne_bytes(b.try_into().expect("chunk of 8 bytes")))
+                    .collect::<Vec<f64>>();
+                spp_experiments::sum_of_squares_slice_f64(&values)
+            });
+        }
+    }
+
+    group.finish();
+}
+
+fn compare_u8_data_structures<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (u8)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        // One byte per element, so 64 elements share each cache line
+        let data_len = input_size_bytes / std::mem::size_of::<u8>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        bench_u8_by_ref_in_group::<Vec<u8>, _>(
+            "Vec<u8> (by reference)",
+            &input_bytes_human,
+            data_len,
+            &mut group,
+        );
+        bench_u8_by_ref_in_group::<VecDeque<u8>, _>(
+            "VecDeque<u8> (by reference)",
+            &input_bytes_human,
+            data_len,
+            &mut group,
+        );
+        bench_u8_by_ref_in_group::<LinkedList<u8>, _>(
+            "LinkedList<u8> (by reference)",
+            &input_bytes_human,
+            data_len,
+            &mut group,
+        );
+
+        bench_iter_with(
+            "Vec<u8> (widening)",
+            &input_bytes_human,
+            &mut group,
+            || expect_bytes::<Vec<u8>>(create_scrambled_bytes(data_len)),
+            |data| spp_experiments::sum_of_squares_u8_widening(black_box(data)),
+        );
+    }
+
+    group.finish();
+}
+
+/// Compare the orders the values are inserted in, see [`Order`]. The values are
+/// reordered before the container is built, outside of the timed region. The
+/// sequence containers keep the order for the kernel to traverse, while the
+/// sets are laid out differently depending on it.
+fn compare_orders<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (order)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        for &order in Order::ALL.iter() {
+            bench_by_ref_with_input::<FloatOrd<f64>, Vec<_>, _, _>(
+                &format!("Vec ({})", order),
+                &input_bytes_human,
+                || create_ordered_data(data_len, Distribution::Uniform, order),
+                &mut group,
+            );
+            bench_by_ref_with_input::<FloatOrd<f64>, VecDeque<_>, _, _>(
+                &format!("VecDeque ({})", order),
+                &input_bytes_human,
+                || create_ordered_data(data_len, Distribution::Uniform, order),
+                &mut group,
+            );
+            bench_by_ref_with_input::<FloatOrd<f64>, LinkedList<_>, _, _>(
+                &format!("LinkedList ({})", order),
+                &input_bytes_human,
+                || create_ordered_data(data_len, Distribution::Uniform, order),
+                &mut group,
+            );
+            bench_by_ref_with_input::<FloatOrd<f64>, HashSet<_>, _, _>(
+                &format!("HashSet ({})", order),
+                &input_bytes_human,
+                || create_ordered_data(data_len, Distribution::Uniform, order),
+                &mut group,
+            );
+            bench_by_ref_with_input::<FloatOrd<f64>, BTreeSet<_>, _, _>(
+                &format!("BTreeSet ({})", order),
+                &input_bytes_human,
+                || create_ordered_data(data_len, Distribution::Uniform, order),
+                &mut group,
+            );
+        }
+    }
+
+    group.finish();
+}
+
+/// Compare the input distributions on a contiguous, a linked, a hashed and a
+/// tree container.
+fn compare_distributions<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (distribution)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        for &distribution in Distribution::ALL.iter() {
+            bench_by_ref_with_input::<FloatOrd<f64>, Vec<_>, _, _>(
+                &format!("Vec ({})", distribution),
+                &input_bytes_human,
+                || create_scrambled_data_with(data_len, distribution),
+                &mut group,
+            );
+            bench_by_ref_with_input::<FloatOrd<f64>, LinkedList<_>, _, _>(
+                &format!("LinkedList ({})", distribution),
+                &input_bytes_human,
+                || create_scrambled_data_with(data_len, distribution),
+                &mut group,
+            );
+            bench_by_ref_with_input::<FloatOrd<f64>, HashSet<_>, _, _>(
+                &format!("HashSet ({})", distribution),
+                &input_bytes_human,
+                || create_distinct_data_with(data_len, distribution),
+                &mut group,
+            );
+            bench_by_ref_with_input::<FloatOrd<f64>, BTreeSet<_>, _, _>(
+                &format!("BTreeSet ({})", distribution),
+                &input_bytes_human,
+                || create_distinct_data_with(data_len, distribution),
+                &mut group,
+            );
+        }
+    }
+
+    group.finish();
+}
+
+// Fractions of non-finite values in the inputs
+const NON_FINITE_FRACTIONS: [f64; 3] = [0.0, 0.001, 0.1];
+
+/// Compare the policies for non-finite values, see [`finite::Policy`], over
+/// inputs with a growing fraction of NaN and infinities. Without any, the
+/// difference to propagating is the cost of checking each value. With some,
+/// the erroring kernel stops at the first one.
+fn compare_non_finite<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (non-finite)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        for &fraction in NON_FINITE_FRACTIONS.iter() {
+            let data: LazyCell<Vec<FloatOrd<f64>>, _> =
+                LazyCell::new(|| create_non_finite_data(data_len, fraction));
+
+            for &policy in finite::Policy::ALL.iter() {
+                let ds_name = format!("Vec ({}, {} % non-finite)", policy, fraction * 100.0);
+                match policy {
+                    finite::Policy::Propagate => bench_iter_with(
+                        &ds_name,
+                        &input_bytes_human,
+                        &mut group,
+                        || {
+                            expect_values(&*data);
+                            &*data
+                        },
+                        |data| sum_of_squares_by_ref(black_box(*data)),
+                    ),
+                    finite::Policy::Skip => bench_iter_with(
+                        &ds_name,
+                        &input_bytes_human,
+                        &mut group,
+                        || {
+                            expect_values(data.iter().filter(|x| x.0.is_finite()));
+                            &*data
+                        },
+                        |data| finite::sum_of_squares_skipping_non_finite(black_box(*data)),
+                    ),
+                    // An error is checked as a NaN sum, which the non-finite
+                    // values make the expected sum
+                    finite::Policy::Error => bench_iter_with(
+                        &ds_name,
+                        &input_bytes_human,
+                        &mut group,
+                        || {
+                            expect_values(&*data);
+                            &*data
+                        },
+                        |data| finite::try_sum_of_squares(black_box(*data)),
+                    ),
+                }
+            }
+        }
+    }
+
+    group.finish();
+}
+
+// Default fractions of subnormal values in the inputs
+const SUBNORMAL_FRACTIONS: [f64; 5] = [0.0, 0.001, 0.01, 0.1, 1.0];
+
+/// Compare inputs with a growing fraction of subnormal values, in
+/// `SOS_SUBNORMAL_FRACTIONS` as a comma-separated list of fractions in [0, 1]
+/// if set. Where the FPU supports it, each input is also summed with
+/// subnormals flushed to zero, see [`ftz`].
+fn compare_subnormals<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (subnormals)");
+
+    let fractions = subnormal_fractions();
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        for &fraction in fractions.iter() {
+            let ds_name = format!("Vec ({} % subnormal)", fraction * 100.0);
+            let flushed_name = format!("Vec ({} % subnormal, FTZ)", fraction * 100.0);
+            let data: LazyCell<Input<Vec<FloatOrd<f64>>>, _> =
+                LazyCell::new(|| Input::of(create_subnormal_data(data_len, fraction)));
+
+            if ftz::supported() && admit(&flushed_name, &input_bytes_human, 1) {
+                // Created before flushing, which would flush the subnormals
+                // the input is made of
+                let data = data.get();
+                let _flush = ftz::FlushToZero::enable();
+                bench_iter(&flushed_name, &input_bytes_human, &mut group, || {
+                    sum_of_squares_by_ref(black_box(data))
+                });
+            }
+            bench_iter_with(
+                &ds_name,
+                &input_bytes_human,
+                &mut group,
+                || data.get(),
+                |data| sum_of_squares_by_ref(black_box(*data)),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+fn subnormal_fractions() -> Vec<f64> {
+    let fractions = match env::var("SOS_SUBNORMAL_FRACTIONS") {
+        Ok(fractions) => fractions,
+        Err(_) => return SUBNORMAL_FRACTIONS.to_vec(),
+    };
+
+    fractions
+        .split(',')
+        .map(|fraction| match fraction.trim().parse::<f64>() {
+            Ok(fraction) if (0.0..=1.0).contains(&fraction) => fraction,
+            _ => {
+                eprintln!(
+                    "error: invalid SOS_SUBNORMAL_FRACTIONS fraction: {}",
+                    fraction
+                );
+                process::exit(1);
+            }
+        })
+        .collect()
+}
+
+/// Compare `Vec<FloatOrd<f64>>` against `Vec<f64>` holding the same values,
+/// with the same generic by-reference kernel, to check that the `FloatOrd`
+/// newtype the rest of the suite uses costs nothing.
+fn compare_wrapper<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (wrapper)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        let data: LazyCell<Input<Vec<FloatOrd<f64>>>, _> =
+            LazyCell::new(|| Input::of(create_scrambled_data(data_len)));
+
+        bench_iter_with(
+            "Vec<FloatOrd<f64>>",
+            &input_bytes_human,
+            &mut group,
+            || data.get(),
+            |data| sum_of_squares_by_ref(black_box(*data)),
+        );
+        bench_iter_with(
+            "Vec<f64>",
+            &input_bytes_human,
+            &mut group,
+            || data.get().iter().map(|x| x.0).collect::<Vec<f64>>(),
+            |floats| sum_of_squares_by_ref(black_box(floats)),
+        );
+    }
+
+    group.finish();
+}
+
+/// Compare the orderable float wrappers `FloatOrd`, `OrderedFloat` and `NotNan`.
+/// Besides the reduction over a `Vec` and a `BTreeSet`, builds the `BTreeSet`
+/// from plain `f64`s in the timed region, which includes `NotNan`'s NaN check
+/// on construction and each wrapper's comparison.
+fn compare_ordering_wrappers<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (ordering wrappers)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        bench_ordering_wrapper_in_group::<FloatOrd<f64>, _>(
+            "FloatOrd",
+            &input_bytes_human,
+            data_len,
+            &mut group,
+        );
+        bench_ordering_wrapper_in_group::<OrderedFloat<f64>, _>(
+            "OrderedFloat",
+            &input_bytes_human,
+            data_len,
+            &mut group,
+        );
+        bench_ordering_wrapper_in_group::<NotNan<f64>, _>(
+            "NotNan",
+            &input_bytes_human,
+            data_len,
+            &mut group,
+        );
+    }
+
+    group.finish();
+}
+
+fn bench_ordering_wrapper_in_group<V, M>(
+    wrapper_name: &str,
+    input_bytes_human: &str,
+    data_len: usize,
+    group: &mut BenchmarkGroup<M>,
+) where
+    V: OrderedElement<f64>,
+    M: Measurement,
+{
+    bench_by_ref_in_group::<V, Vec<V>, _, _>(
+        &format!("Vec<{}> (by reference)", wrapper_name),
+        input_bytes_human,
+        data_len,
+        group,
+    );
+    bench_by_ref_in_group::<V, BTreeSet<V>, _, _>(
+        &format!("BTreeSet<{}> (by reference)", wrapper_name),
+        input_bytes_human,
+        data_len,
+        group,
+    );
+
+    bench_batched_with(
+        &format!("BTreeSet<{}> (construction)", wrapper_name),
+        input_bytes_human,
+        group,
+        || {
+            // Distinct, so that the set holds every value
+            expect::<V, Vec<_>, _>(create_distinct_data(data_len))
+                .into_iter()
+                .map(|x| x.inner())
+                .collect::<Vec<f64>>()
+        },
+        |floats| floats.clone(),
+        |floats| {
+            let set = floats.into_iter().map(V::create).collect::<BTreeSet<V>>();
+            sum_of_squares_by_ref(black_box(&set))
+        },
+    );
+}
+
+/// Compare the slice kernels on `&[FloatOrd<f64>]` and on plain `&[f64]`.
+fn compare_slices<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (slice)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        let data: LazyCell<Input<Vec<FloatOrd<f64>>>, _> =
+            LazyCell::new(|| Input::of(create_scrambled_data(data_len)));
+
+        bench_iter_with(
+            "&[FloatOrd<f64>]",
+            &input_bytes_human,
+            &mut group,
+            || data.get(),
+            |data| spp_experiments::sum_of_squares_slice(black_box(data.as_slice())),
+        );
+        bench_iter_with(
+            "&[f64]",
+            &input_bytes_human,
+            &mut group,
+            || data.get().iter().map(|x| x.0).collect::<Vec<f64>>(),
+            |floats| spp_experiments::sum_of_squares_slice_f64(black_box(floats.as_slice())),
+        );
+    }
+
+    group.finish();
+}
+
+/// Compare the plain iterator kernel against manually unrolled variants on
+/// every container of the main matrix.
+fn compare_unrolling<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut cases = container_cases!(
+        "plain",
+        bench_sum_in_group,
+        FloatOrd<f64>,
+        [FloatOrd<f64>],
+        [M]
+    );
+    cases.extend(container_cases!(
+        "unroll 2",
+        bench_unrolled_2_in_group,
+        FloatOrd<f64>,
+        [FloatOrd<f64>],
+        [M]
+    ));
+    cases.extend(container_cases!(
+        "unroll 4",
+        bench_unrolled_4_in_group,
+        FloatOrd<f64>,
+        [FloatOrd<f64>],
+        [M]
+    ));
+    cases.extend(container_cases!(
+        "unroll 8",
+        bench_unrolled_8_in_group,
+        FloatOrd<f64>,
+        [FloatOrd<f64>],
+        [M]
+    ));
+    compare_cases(
+        "Sum of squares (unrolled)",
+        std::mem::size_of::<f64>(),
+        (start_pow2, end_pow2, step_pow2),
+        c,
+        cases,
+    );
+}
+
+/// Benchmark `kernel` on the container of a case, reading the data by
+/// reference as [`bench_by_ref_in_group`] does, and checking the sum of the
+/// kernel like it if sums are verified.
+fn bench_kernel_in_group<V, T, M>(
+    ds_name: &str,
+    parameter_name: &str,
+    data_len: usize,
+    group: &mut BenchmarkGroup<M>,
+    kernel: impl Fn(&T) -> f64,
+) where
+    V: Element<f64>,
+    T: iter::FromIterator<V>,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a V>,
+    M: Measurement,
+{
+    if !admit(ds_name, parameter_name, 1) {
+        return;
+    }
+
+    let data: T = create_checked_data(ds_name, parameter_name, data_len, &kernel);
+
+    verify_residency(ds_name, parameter_name, &data);
+    bench_iter(ds_name, parameter_name, group, move || {
+        kernel(black_box(&data))
+    });
+}
+
+fn bench_sum_in_group<V, T, M>(
+    ds_name: &str,
+    parameter_name: &str,
+    data_len: usize,
+    group: &mut BenchmarkGroup<M>,
+) where
+    V: Element<f64>,
+    T: iter::FromIterator<V>,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a V>,
+    M: Measurement,
+{
+    bench_kernel_in_group(ds_name, parameter_name, data_len, group, |data: &T| {
+        sum_of_squares_by_ref(data)
+    });
+}
+
+fn bench_unrolled_2_in_group<V, T, M>(
+    ds_name: &str,
+    parameter_name: &str,
+    data_len: usize,
+    group: &mut BenchmarkGroup<M>,
+) where
+    V: Element<f64>,
+    T: iter::FromIterator<V>,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a V>,
+    M: Measurement,
+{
+    bench_kernel_in_group(ds_name, parameter_name, data_len, group, |data: &T| {
+        unroll::sum_of_squares_unrolled_2(data)
+    });
+}
+
+fn bench_unrolled_4_in_group<V, T, M>(
+    ds_name: &str,
+    parameter_name: &str,
+    data_len: usize,
+    group: &mut BenchmarkGroup<M>,
+) where
+    V: Element<f64>,
+    T: iter::FromIterator<V>,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a V>,
+    M: Measurement,
+{
+    bench_kernel_in_group(ds_name, parameter_name, data_len, group, |data: &T| {
+        unroll::sum_of_squares_unrolled_4(data)
+    });
+}
+
+fn bench_unrolled_8_in_group<V, T, M>(
+    ds_name: &str,
+    parameter_name: &str,
+    data_len: usize,
+    group: &mut BenchmarkGroup<M>,
+) where
+    V: Element<f64>,
+    T: iter::FromIterator<V>,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a V>,
+    M: Measurement,
+{
+    bench_kernel_in_group(ds_name, parameter_name, data_len, group, |data: &T| {
+        unroll::sum_of_squares_unrolled_8(data)
+    });
+}
+
+/// Compare functionally identical formulations of the kernel, `map(..).sum()`,
+/// `fold(..)` and a hand-written `while let` loop, on every container of the
+/// main matrix.
+fn compare_styles<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut cases = container_cases!(
+        "sum",
+        bench_sum_in_group,
+        FloatOrd<f64>,
+        [FloatOrd<f64>],
+        [M]
+    );
+    cases.extend(container_cases!(
+        "fold",
+        bench_fold_in_group,
+        FloatOrd<f64>,
+        [FloatOrd<f64>],
+        [M]
+    ));
+    cases.extend(container_cases!(
+        "while",
+        bench_while_in_group,
+        FloatOrd<f64>,
+        [FloatOrd<f64>],
+        [M]
+    ));
+    compare_cases(
+        "Sum of squares (style)",
+        std::mem::size_of::<f64>(),
+        (start_pow2, end_pow2, step_pow2),
+        c,
+        cases,
+    );
+}
+
+fn bench_fold_in_group<V, T, M>(
+    ds_name: &str,
+    parameter_name: &str,
+    data_len: usize,
+    group: &mut BenchmarkGroup<M>,
+) where
+    V: Element<f64>,
+    T: iter::FromIterator<V>,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a V>,
+    M: Measurement,
+{
+    bench_kernel_in_group(ds_name, parameter_name, data_len, group, |data: &T| {
+        spp_experiments::sum_of_squares_fold(data)
+    });
+}
+
+fn bench_while_in_group<V, T, M>(
+    ds_name: &str,
+    parameter_name: &str,
+    data_len: usize,
+    group: &mut BenchmarkGroup<M>,
+) where
+    V: Element<f64>,
+    T: iter::FromIterator<V>,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a V>,
+    M: Measurement,
+{
+    bench_kernel_in_group(ds_name, parameter_name, data_len, group, |data: &T| {
+        spp_experiments::sum_of_squares_while(data)
+    });
+}
+
+/// Compare the monomorphized kernel against one that consumes a
+/// `Box<dyn Iterator>`, to measure the cost of dynamic dispatch per element on
+/// every container of the main matrix.
+fn compare_dispatch<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut cases = container_cases!(
+        "static",
+        bench_sum_in_group,
+        FloatOrd<f64>,
+        [FloatOrd<f64>],
+        [M]
+    );
+    cases.extend(container_cases!(
+        "dyn",
+        bench_dyn_in_group,
+        FloatOrd<f64>,
+        [FloatOrd<f64>],
+        [M]
+    ));
+    compare_cases(
+        "Sum of squares (dispatch)",
+        std::mem::size_of::<f64>(),
+        (start_pow2, end_pow2, step_pow2),
+        c,
+        cases,
+    );
+}
+
+/// Benchmark the dynamic dispatch kernel on one container. Boxing the iterator
+/// is a single small allocation per iteration, included in the measurement.
+fn bench_dyn_in_group<V, T, M>(
+    ds_name: &str,
+    parameter_name: &str,
+    data_len: usize,
+    group: &mut BenchmarkGroup<M>,
+) where
+    V: Element<f64>,
+    T: iter::FromIterator<V>,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a V>,
+    M: Measurement,
+{
+    bench_kernel_in_group(ds_name, parameter_name, data_len, group, |data: &T| {
+        // Hide the concrete type so the calls can't be devirtualized
+        let values: Box<dyn Iterator<Item = f64>> = Box::new(data.into_iter().map(|x| x.inner()));
+        spp_experiments::sum_of_squares_dyn(black_box(values))
+    });
+}
+
+/// Compare the iterator kernel against an index-based loop over `0..len` on
+/// the containers that support indexing.
+fn compare_indexing<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (indexed)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        let data: LazyCell<Input<Vec<FloatOrd<f64>>>, _> =
+            LazyCell::new(|| Input::of(create_scrambled_data(data_len)));
+        bench_iter_with(
+            "Vec (iterator)",
+            &input_bytes_human,
+            &mut group,
+            || data.get(),
+            |data| sum_of_squares_by_ref(black_box(*data)),
+        );
+        bench_iter_with(
+            "Vec (indexed)",
+            &input_bytes_human,
+            &mut group,
+            || data.get(),
+            |data| spp_experiments::sum_of_squares_indexed(black_box(*data)),
+        );
+        bench_iter_with(
+            "slice (iterator)",
+            &input_bytes_human,
+            &mut group,
+            || data.get().as_slice(),
+            |data| spp_experiments::sum_of_squares_by_ref(black_box(*data)),
+        );
+        bench_iter_with(
+            "slice (indexed)",
+            &input_bytes_human,
+            &mut group,
+            || data.get().as_slice(),
+            |data| spp_experiments::sum_of_squares_indexed(black_box(*data)),
+        );
+        drop(data);
+
+        let data: LazyCell<Input<VecDeque<FloatOrd<f64>>>, _> =
+            LazyCell::new(|| Input::of(create_scrambled_data(data_len)));
+        bench_iter_with(
+            "VecDeque (iterator)",
+            &input_bytes_human,
+            &mut group,
+            || data.get(),
+            |data| sum_of_squares_by_ref(black_box(*data)),
+        );
+        bench_iter_with(
+            "VecDeque (indexed)",
+            &input_bytes_human,
+            &mut group,
+            || data.get(),
+            |data| spp_experiments::sum_of_squares_indexed(black_box(*data)),
+        );
+    }
+
+    group.finish();
+}
+
+// Block sizes for the chunked reduction, as powers of two in bytes: 1 kB to 4 MB
+const BLOCK_START_POW: u32 = 10;
+const BLOCK_END_POW: u32 = 22;
+
+/// Sweep the block size of the chunked sequential reduction at each input
+/// size, to relate the best blocking factor to the L1/L2 sizes.
+fn compare_block_sizes<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (chunked)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        let data: LazyCell<Input<Vec<FloatOrd<f64>>>, _> =
+            LazyCell::new(|| Input::of(create_scrambled_data(data_len)));
+
+        // Blocks larger than the input are the same as no blocking
+        for block_size_bytes in (BLOCK_START_POW..=BLOCK_END_POW)
+            .map(|pow| 1 << pow)
+            .filter(|&block_size_bytes| block_size_bytes <= input_size_bytes)
+        {
+            let chunk_len = block_size_bytes / std::mem::size_of::<f64>();
+            bench_iter_with(
+                &format!("Vec (block {})", human_readable_size(block_size_bytes)),
+                &input_bytes_human,
+                &mut group,
+                || data.get(),
+                |data| spp_experiments::sum_of_squares_chunked(black_box(*data), chunk_len),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+// Minimum number of elements per rayon task for the explicit fold-reduce,
+// 2 kB to 512 kB of f64s
+const RAYON_CHUNK_LENS: [usize; 5] = [256, 1024, 4096, 16384, 65536];
+
+/// Compare the one-liner rayon `map(..).sum()` against an explicit
+/// `fold(..).reduce(..)` with a swept chunk length.
+fn compare_rayon_styles<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (rayon)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        let data: LazyCell<Input<Vec<FloatOrd<f64>>>, _> =
+            LazyCell::new(|| Input::of(create_scrambled_data(data_len)));
+
+        bench_iter_with(
+            "Vec (par_iter map sum)",
+            &input_bytes_human,
+            &mut group,
+            || data.get(),
+            |data| parallel::sum_of_squares_par_map_sum(black_box(data)),
+        );
+        for &chunk_len in RAYON_CHUNK_LENS.iter() {
+            bench_iter_with(
+                &format!("Vec (fold-reduce, chunk {})", chunk_len),
+                &input_bytes_human,
+                &mut group,
+                || data.get(),
+                |data| parallel::sum_of_squares_par_fold_reduce(black_box(data), chunk_len),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+/// Sweep the number of rayon threads at each input size on every container, to
+/// find the size at which parallelism starts paying off, and derive speedup and
+/// parallel efficiency per size into a scaling table.
+fn compare_thread_counts<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, scaling::GROUP);
+
+    let pools = parallel::thread_counts(parallel::max_threads())
+        .into_iter()
+        .map(|threads| (threads, parallel::thread_pool(threads)))
+        .collect::<Vec<_>>();
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        bench_par_in_group::<FloatOrd<f64>, Vec<_>, _>(
+            "Vec",
+            &input_bytes_human,
+            data_len,
+            &pools,
+            &mut group,
+        );
+        bench_par_in_group::<FloatOrd<f64>, VecDeque<_>, _>(
+            "VecDeque",
+            &input_bytes_human,
+            data_len,
+            &pools,
+            &mut group,
+        );
+        bench_par_in_group::<FloatOrd<f64>, LinkedList<_>, _>(
+            "LinkedList",
+            &input_bytes_human,
+            data_len,
+            &pools,
+            &mut group,
+        );
+        bench_par_in_group::<FloatOrd<f64>, HashSet<_>, _>(
+            "HashSet",
+            &input_bytes_human,
+            data_len,
+            &pools,
+            &mut group,
+        );
+        bench_par_in_group::<FloatOrd<f64>, BTreeSet<_>, _>(
+            "BTreeSet",
+            &input_bytes_human,
+            data_len,
+            &pools,
+            &mut group,
+        );
+    }
+
+    group.finish();
+
+    // The table is derived from the wall-time estimates just written
+    if measures_wall_time() {
+        let table = results::read_measured().map(|estimates| scaling::scaling_table(&estimates));
+        match table.and_then(|rows| scaling::write_table(&rows)) {
+            Ok(()) => println!("Scaling table written to {}", scaling::path().display()),
+            Err(e) => eprintln!("warning: could not write the scaling table: {}", e),
+        }
+    }
+}
+
+fn bench_par_in_group<V, T, M>(
+    ds_name: &str,
+    parameter_name: &str,
+    data_len: usize,
+    pools: &[(usize, rayon::ThreadPool)],
+    group: &mut BenchmarkGroup<M>,
+) where
+    V: Element<f64> + Sync,
+    T: iter::FromIterator<V> + Sync,
+    for<'a> T: IntoParallelRefIterator<'a, Item = &'a V>,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a V>,
+    M: Measurement,
+{
+    let data: LazyCell<Input<T>, _> = LazyCell::new(|| Input::of(create_scrambled_data(data_len)));
+
+    for (threads, pool) in pools {
+        bench_iter_with(
+            &format!("{} ({} threads)", ds_name, threads),
+            parameter_name,
+            group,
+            || data.get(),
+            |data| pool.install(|| parallel::sum_of_squares_par(black_box(*data))),
+        );
+    }
+}
+
+/// Compare ndarray's `Array1<f64>` against `Vec<f64>` with the same generic
+/// kernel, and against ndarray's own `dot` product of the array with itself.
+#[cfg(feature = "ndarray")]
+fn compare_ndarray<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (ndarray)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        let data: LazyCell<Input<Vec<f64>>, _> =
+            LazyCell::new(|| Input::of(create_scrambled_data(data_len)));
+        let array = LazyCell::new(|| data.map(|data| ndarray::Array1::from_vec(data.clone())));
+
+        bench_iter_with(
+            "Vec<f64>",
+            &input_bytes_human,
+            &mut group,
+            || data.get(),
+            |data| sum_of_squares_by_ref(black_box(*data)),
+        );
+        bench_iter_with(
+            "Array1<f64>",
+            &input_bytes_human,
+            &mut group,
+            || array.get(),
+            |array| sum_of_squares_by_ref(black_box(*array)),
+        );
+        bench_iter_with(
+            "Array1<f64> (dot)",
+            &input_bytes_human,
+            &mut group,
+            || array.get(),
+            |array| {
+                let array = black_box(*array);
+                array.dot(array)
+            },
+        );
+    }
+
+    group.finish();
+}
+
+/// Compare nalgebra's `DVector<f64>` against `Vec<f64>` with the same generic
+/// kernel, and against nalgebra's own `norm_squared`.
+#[cfg(feature = "nalgebra")]
+fn compare_nalgebra<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (nalgebra)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        let data: LazyCell<Input<Vec<f64>>, _> =
+            LazyCell::new(|| Input::of(create_scrambled_data(data_len)));
+        let vector = LazyCell::new(|| data.map(|data| nalgebra::DVector::from_vec(data.clone())));
+
+        bench_iter_with(
+            "Vec<f64>",
+            &input_bytes_human,
+            &mut group,
+            || data.get(),
+            |data| sum_of_squares_by_ref(black_box(*data)),
+        );
+        bench_iter_with(
+            "DVector<f64>",
+            &input_bytes_human,
+            &mut group,
+            || vector.get(),
+            |vector| sum_of_squares_by_ref(black_box(*vector)),
+        );
+        bench_iter_with(
+            "DVector<f64> (norm_squared)",
+            &input_bytes_human,
+            &mut group,
+            || vector.get(),
+            |vector| black_box(*vector).norm_squared(),
+        );
+    }
+
+    group.finish();
+}
+
+/// Compare Arrow's `Float64Array` against `Vec<f64>`, both over the raw values
+/// buffer and through the iterator that checks the null bitmap per value.
+#[cfg(feature = "arrow")]
+fn compare_arrow<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (arrow)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        let data: LazyCell<Input<Vec<f64>>, _> =
+            LazyCell::new(|| Input::of(create_scrambled_data(data_len)));
+        let array =
+            LazyCell::new(|| data.map(|data| arrow::array::Float64Array::from(data.clone())));
+
+        bench_iter_with(
+            "Vec<f64>",
+            &input_bytes_human,
+            &mut group,
+            || data.get(),
+            |data| spp_experiments::sum_of_squares_slice_f64(black_box(data)),
+        );
+        bench_iter_with(
+            "Float64Array (values)",
+            &input_bytes_human,
+            &mut group,
+            || array.get(),
+            |array| spp_experiments::sum_of_squares_slice_f64(black_box(*array).values()),
+        );
+        bench_iter_with(
+            "Float64Array (null-checked)",
+            &input_bytes_human,
+            &mut group,
+            || array.get(),
+            |array| {
+                black_box(*array)
+                    .iter()
+                    .flatten()
+                    .map(|x| x.powi(2))
+                    .sum::<f64>()
+            },
+        );
+    }
+
+    group.finish();
+}
+
+/// Compare the GPU reduction with and without the host to device transfer
+/// against the CPU on the same single-precision data.
+#[cfg(feature = "gpu")]
+fn compare_gpu<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let gpu = match gpu::Gpu::new() {
+        Some(gpu) => gpu,
+        None => {
+            eprintln!("warning: no GPU adapter found, skipping the GPU benchmarks");
+            return;
+        }
+    };
+
+    let mut group = new_group(c, "Sum of squares (GPU)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        // The GPU works on f32, so the same number of bytes holds twice as many
+        // elements as in the f64 benchmarks
+        let data_len = input_size_bytes / std::mem::size_of::<f32>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        let data: LazyCell<Input<Vec<f32>>, _> = LazyCell::new(|| {
+            Input::of(
+                create_scrambled_data::<FloatOrd<f64>, Vec<_>, _>(data_len)
+                    .into_iter()
+                    .map(|x| x.0 as f32)
+                    .collect(),
+            )
+        });
+
+        bench_iter_with(
+            "Vec<f32> (CPU)",
+            &input_bytes_human,
+            &mut group,
+            || data.get(),
+            |data| black_box(*data).iter().map(|x| x.powi(2)).sum::<f32>(),
+        );
+        if !fits_on_gpu(&gpu, &input_bytes_human, input_size_bytes) {
+            continue;
+        }
+        bench_iter_with(
+            "GPU (including transfer)",
+            &input_bytes_human,
+            &mut group,
+            || data.get(),
+            |data| gpu.sum_of_squares_with_transfer(black_box(data)),
+        );
+        bench_iter_with(
+            "GPU (excluding transfer)",
+            &input_bytes_human,
+            &mut group,
+            || gpu.upload(data.get()),
+            |resident| gpu.sum_of_squares(black_box(resident)),
+        );
+    }
+
+    group.finish();
+}
+
+/// Whether an input of `input_size_bytes` fits in a buffer of `gpu`, warning
+/// about each selected GPU case that is skipped if not.
+#[cfg(feature = "gpu")]
+fn fits_on_gpu(gpu: &gpu::Gpu, parameter_name: &str, input_size_bytes: usize) -> bool {
+    let limit = gpu.max_input_bytes();
+    if input_size_bytes as u64 <= limit {
+        return true;
+    }
+
+    for &ds_name in ["GPU (including transfer)", "GPU (excluding transfer)"].iter() {
+        if !selected(ds_name, parameter_name) {
+            continue;
+        }
+        let id = case_id(ds_name, parameter_name);
+        eprintln!(
+            "warning: skipping {}: needs a buffer of {}, above the GPU limit of {}",
+            id,
+            human_readable_size(input_size_bytes),
+            human_readable_size(limit as usize)
+        );
+        if !DRY_RUN.load(Ordering::Relaxed) {
+            metadata::record(&id, "skipped_buffer_bytes", input_size_bytes);
+            metadata::record(&id, "skipped_gpu_limit_bytes", limit);
+        }
+    }
+    false
+}
+
+/// Compare the scalar loop against the portable SIMD kernel and the
+/// width-specific intrinsic kernels this CPU supports, all on a plain
+/// `Vec<f64>`.
+#[cfg(target_arch = "x86_64")]
+fn compare_x86_intrinsics<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (x86 intrinsics)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        let data: LazyCell<Input<Vec<f64>>, _> =
+            LazyCell::new(|| Input::of(create_scrambled_data(data_len)));
+
+        bench_iter_with(
+            "Vec<f64> (scalar)",
+            &input_bytes_human,
+            &mut group,
+            || data.get(),
+            |data| black_box(*data).iter().map(|x| x.powi(2)).sum::<f64>(),
+        );
+        #[cfg(feature = "simd")]
+        bench_iter_with(
+            "Vec<f64> (portable SIMD)",
+            &input_bytes_human,
+            &mut group,
+            || data.get(),
+            |data| simd::sum_of_squares_simd_f64(black_box(data)),
+        );
+        if x86::avx2_available() {
+            bench_iter_with(
+                "Vec<f64> (AVX2)",
+                &input_bytes_human,
+                &mut group,
+                || data.get(),
+                |data| x86::sum_of_squares_avx2(black_box(data)),
+            );
+        }
+        if x86::avx512_available() {
+            bench_iter_with(
+                "Vec<f64> (AVX-512)",
+                &input_bytes_human,
+                &mut group,
+                || data.get(),
+                |data| x86::sum_of_squares_avx512(black_box(data)),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+/// Compare the scalar loop against the portable SIMD kernel and the NEON
+/// kernel, all on a plain `Vec<f64>`.
+#[cfg(target_arch = "aarch64")]
+fn compare_aarch64_intrinsics<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let mut group = new_group(c, "Sum of squares (aarch64 intrinsics)");
+
+    for input_size_bytes in input_sizes(start_pow2, end_pow2, step_pow2) {
+        let data_len = input_size_bytes / std::mem::size_of::<f64>();
+        configure_for_size(&mut group, input_size_bytes, data_len);
+        let input_bytes_human = human_readable_size(input_size_bytes);
+
+        let data: LazyCell<Input<Vec<f64>>, _> =
+            LazyCell::new(|| Input::of(create_scrambled_data(data_len)));
+
+        bench_iter_with(
+            "Vec<f64> (scalar)",
+            &input_bytes_human,
+            &mut group,
+            || data.get(),
+            |data| black_box(*data).iter().map(|x| x.powi(2)).sum::<f64>(),
+        );
+        #[cfg(feature = "simd")]
+        bench_iter_with(
+            "Vec<f64> (portable SIMD)",
+            &input_bytes_human,
+            &mut group,
+            || data.get(),
+            |data| simd::sum_of_squares_simd_f64(black_box(data)),
+        );
+        if aarch64::neon_available() {
+            bench_iter_with(
+                "Vec<f64> (NEON)",
+                &input_bytes_human,
+                &mut group,
+                || data.get(),
+                |data| aarch64::sum_of_squares_neon(black_box(data)),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+/// The cases of the main matrix: every container, by reference and by value.
+fn main_matrix_cases<V, M, P>() -> Vec<(&'static str, Case<M>)>
+where
+    V: OrderedElement<P>,
+    P: Primitive + Checked,
+    M: Measurement,
+{
+    let mut cases = container_cases!("by reference", bench_by_ref_in_group, V, [V], [M, P]);
+    cases.extend(container_cases!(
+        "by value",
+        bench_by_val_in_group,
+        V,
+        [V],
+        [M, P]
+    ));
+
+    cases
+}
+
+/// The cases of the main matrix over integer elements.
+fn int_matrix_cases<V, M, P>() -> Vec<(&'static str, Case<M>)>
+where
+    V: Int<P>,
+    P: IntPrimitive + Checked,
+    M: Measurement,
+    rand::distributions::Standard: rand::distributions::Distribution<P>,
+{
+    let mut cases = container_cases!("by reference", bench_int_by_ref_in_group, V, [V], [M, P]);
+    cases.extend(container_cases!(
+        "by value",
+        bench_int_by_val_in_group,
+        V,
+        [V],
+        [M, P]
+    ));
+
+    cases
+}
+
+/// Measure `clone` alone on every container of the main matrix, the setup the
+/// by-value cases run before each iteration. Its cost, and the caches it
+/// leaves warm or dirty, are part of what the by-value cases see.
+fn compare_clones<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let cases = container_cases!(
+        "clone",
+        bench_clone_in_group,
+        FloatOrd<f64>,
+        [FloatOrd<f64>],
+        [M, f64]
+    );
+    compare_cases(
+        "Clone",
+        std::mem::size_of::<f64>(),
+        (start_pow2, end_pow2, step_pow2),
+        c,
+        cases,
+    );
+}
+
+/// Benchmark cloning the container. The clones are dropped outside of the
+/// timed region, as the by-value cases consume theirs.
+fn bench_clone_in_group<V, T, M, P>(
+    ds_name: &str,
+    parameter_name: &str,
+    data_len: usize,
+    group: &mut BenchmarkGroup<M>,
+) where
+    V: Element<P>,
+    P: Primitive,
+    T: iter::FromIterator<V> + Clone,
+    M: Measurement,
+{
+    if !admit(ds_name, parameter_name, 2) {
+        return;
+    }
+
+    let data: T = create_distinct_data(data_len);
+
+    bench_batched(
+        ds_name,
+        parameter_name,
+        group,
+        || (),
+        |()| NoSum(data.clone()),
+    );
+}
+
+/// Measure building every container of the main matrix from an iterator, as
+/// `create_scrambled_data` does, apart from traversing it.
+fn compare_construction<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let cases = container_cases!(
+        "collect",
+        bench_construction_in_group,
+        FloatOrd<f64>,
+        [FloatOrd<f64>],
+        [M, f64]
+    );
+    compare_cases(
+        "Construction",
+        std::mem::size_of::<f64>(),
+        (start_pow2, end_pow2, step_pow2),
+        c,
+        cases,
+    );
+}
+
+/// Benchmark collecting the container from an iterator over the input. The
+/// random values are generated beforehand, so that only the construction is
+/// timed, and the containers are dropped outside of the timed region.
+fn bench_construction_in_group<V, T, M, P>(
+    ds_name: &str,
+    parameter_name: &str,
+    data_len: usize,
+    group: &mut BenchmarkGroup<M>,
+) where
+    V: Element<P>,
+    P: Primitive,
+    T: iter::FromIterator<V>,
+    M: Measurement,
+{
+    if !admit(ds_name, parameter_name, 2) {
+        return;
+    }
+
+    let values: Vec<V> = create_distinct_data(data_len);
+
+    bench_batched(
+        ds_name,
+        parameter_name,
+        group,
+        || (),
+        |()| NoSum(values.iter().copied().collect::<T>()),
+    );
+}
+
+/// Measure dropping every container of the main matrix, which the by-value
+/// cases pay for at the end of each iteration and the by-reference cases don't.
+fn compare_drops<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let cases = container_cases!(
+        "drop",
+        bench_drop_in_group,
+        FloatOrd<f64>,
+        [FloatOrd<f64>],
+        [M, f64]
+    );
+    compare_cases(
+        "Drop",
+        std::mem::size_of::<f64>(),
+        (start_pow2, end_pow2, step_pow2),
+        c,
+        cases,
+    );
+}
+
+/// Benchmark dropping a clone of the container, built in the untimed setup.
+fn bench_drop_in_group<V, T, M, P>(
+    ds_name: &str,
+    parameter_name: &str,
+    data_len: usize,
+    group: &mut BenchmarkGroup<M>,
+) where
+    V: Element<P>,
+    P: Primitive,
+    T: iter::FromIterator<V> + Clone,
+    M: Measurement,
+{
+    if !admit(ds_name, parameter_name, 2) {
+        return;
+    }
+
+    let data: T = create_distinct_data(data_len);
+
+    bench_batched(ds_name, parameter_name, group, || data.clone(), drop);
+}
+
+/// Measure iterating over every container of the main matrix without any
+/// arithmetic, the traversal part of the by-reference cases.
+fn compare_traversal<M: Measurement>(
+    start_pow2: u32,
+    end_pow2: u32,
+    step_pow2: u32,
+    c: &mut Criterion<M>,
+) {
+    let cases = container_cases!(
+        "traverse",
+        bench_traversal_in_group,
+        FloatOrd<f64>,
+        [FloatOrd<f64>],
+        [M, f64]
+    );
+    compare_cases(
+        "Traversal",
+        std::mem::size_of::<f64>(),
+        (start_pow2, end_pow2, step_pow2),
+        c,
+        cases,
+    );
+}
+
+fn bench_traversal_in_group<V, T, M, P>(
+    ds_name: &str,
+    parameter_name: &str,
+    data_len: usize,
+    group: &mut BenchmarkGroup<M>,
+) where
+    V: Element<P>,
+    P: Primitive + Bits,
+    T: iter::FromIterator<V>,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a V>,
+    M: Measurement,
+{
+    if !admit(ds_name, parameter_name, 1) {
+        return;
+    }
+
+    let data: T = create_distinct_data(data_len);
+
+    verify_residency(ds_name, parameter_name, &data);
+    bench_iter(ds_name, parameter_name, group, move || {
+        NoSum(spp_experiments::traverse(black_box(&data)))
+    });
+}
+
+fn bench_by_ref_in_group<V, T, M, P>(
+    ds_name: &str,
+    parameter_name: &str,
+    data_len: usize,
+    group: &mut BenchmarkGroup<M>,
+) where
+    V: Element<P>,
+    P: Primitive + Checked,
+    T: iter::FromIterator<V> + iter::IntoIterator<Item = V>,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a V>,
+    M: Measurement,
+{
+    if !admit(ds_name, parameter_name, 1) {
+        return;
+    }
+
+    // Create concrete data-structure using FromIterator<V>
+    let data: T = create_checked_data(ds_name, parameter_name, data_len, |data| {
+        sum_of_squares_by_ref(data)
+    });
+
+    bench_by_ref_with_data(ds_name, parameter_name, data, group);
+}
+
+fn bench_by_ref_with_data<V, T, M, P>(
+    ds_name: &str,
+    parameter_name: &str,
+    data: T,
+    group: &mut BenchmarkGroup<M>,
+) where
+    V: Element<P>,
+    P: Primitive + Checked,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a V>,
+    M: Measurement,
+{
+    verify_residency(ds_name, parameter_name, &data);
+    bench_iter(ds_name, parameter_name, group, move || {
+        sum_of_squares_by_ref(black_box(&data))
+    });
+}
+
+/// Like [`bench_by_ref_with_data`], over a container of the values `create`
+/// makes once the case is selected, see [`bench_iter_with`]. The container
+/// must hold every value, so a set needs distinct ones.
+fn bench_by_ref_with_input<V, T, M, P>(
+    ds_name: &str,
+    parameter_name: &str,
+    create: impl FnOnce() -> Vec<V>,
+    group: &mut BenchmarkGroup<M>,
+) where
+    V: Element<P>,
+    P: Primitive + Checked,
+    T: iter::FromIterator<V>,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a V>,
+    M: Measurement,
+{
+    if !admit(ds_name, parameter_name, 1) {
+        return;
+    }
+
+    let values = create();
+    let data_len = values.len();
+    let data: T = expect(values);
+    check_len(ds_name, parameter_name, &data, data_len);
+    bench_by_ref_with_data(ds_name, parameter_name, data, group);
+}
+
+fn bench_by_val_in_group<V, T, M, P>(
+    ds_name: &str,
+    parameter_name: &str,
+    data_len: usize,
+    group: &mut BenchmarkGroup<M>,
+) where
+    V: Element<P>,
+    P: Primitive + Checked,
+    T: iter::FromIterator<V> + iter::IntoIterator<Item = V> + Clone + iter::IntoIterator<Item = V>,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a V>,
+    M: Measurement,
+{
+    if !admit(ds_name, parameter_name, 2) {
+        return;
+    }
+
+    // Create concrete data-structure using FromIterator<V>
+    let data: T = create_checked_data(ds_name, parameter_name, data_len, |data: &T| {
+        sum_of_squares_by_move(data.clone())
+    });
+
+    verify_residency(ds_name, parameter_name, &data);
+    bench_batched(
+        ds_name,
+        parameter_name,
+        group,
+        move || data.clone(),
+        |data| sum_of_squares_by_move(black_box(data)),
+    );
+}
+
+fn bench_int_by_ref_in_group<V, T, M, P>(
+    ds_name: &str,
+    parameter_name: &str,
+    data_len: usize,
+    group: &mut BenchmarkGroup<M>,
+) where
+    V: Int<P>,
+    P: IntPrimitive + Checked,
+    T: iter::FromIterator<V>,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a V>,
+    M: Measurement,
+    rand::distributions::Standard: rand::distributions::Distribution<P>,
+{
+    if !admit(ds_name, parameter_name, 1) {
+        return;
+    }
+
+    let data: T = create_checked_ints(ds_name, parameter_name, data_len, |data| {
+        spp_experiments::sum_of_squares_int_by_ref(data)
+    });
+
+    verify_residency(ds_name, parameter_name, &data);
+    bench_iter(ds_name, parameter_name, group, move || {
+        spp_experiments::sum_of_squares_int_by_ref(black_box(&data))
+    });
+}
+
+fn bench_int_by_val_in_group<V, T, M, P>(
+    ds_name: &str,
+    parameter_name: &str,
+    data_len: usize,
+    group: &mut BenchmarkGroup<M>,
+) where
+    V: Int<P>,
+    P: IntPrimitive + Checked,
+    T: iter::FromIterator<V> + iter::IntoIterator<Item = V> + Clone,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a V>,
+    M: Measurement,
+    rand::distributions::Standard: rand::distributions::Distribution<P>,
+{
+    if !admit(ds_name, parameter_name, 2) {
+        return;
+    }
+
+    let data: T = create_checked_ints(ds_name, parameter_name, data_len, |data: &T| {
+        spp_experiments::sum_of_squares_int_by_move(data.clone())
+    });
+
+    verify_residency(ds_name, parameter_name, &data);
+    bench_batched(
+        ds_name,
+        parameter_name,
+        group,
+        move || data.clone(),
+        |data| spp_experiments::sum_of_squares_int_by_move(black_box(data)),
+    );
+}
+
+fn bench_u8_by_ref_in_group<T, M>(
+    ds_name: &str,
+    parameter_name: &str,
+    data_len: usize,
+    group: &mut BenchmarkGroup<M>,
+) where
+    T: iter::FromIterator<u8>,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a u8>,
+    M: Measurement,
+{
+    if !admit(ds_name, parameter_name, 1) {
+        return;
+    }
+
+    let data: T = expect_bytes(create_scrambled_bytes(data_len));
+    check_len(ds_name, parameter_name, &data, data_len);
+    bench_iter(ds_name, parameter_name, group, move || {
+        spp_experiments::sum_of_squares_u8_by_ref(black_box(&data))
+    });
+}
+
+// Benchmark registration
+
+/// Set when the bench binary is started with `--dry-run`. Cases are then only
+/// probed to estimate the duration of the campaign.
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+/// Set along with `DRY_RUN` when the bench binary is started with `--list`.
+/// Cases are then not run at all, and their duration is modelled from their
+/// footprint. Their inputs aren't created either, see [`admit`].
+static LIST: AtomicBool = AtomicBool::new(false);
+/// Input size in bytes of the cases being registered, for the dry run's
+/// footprints and the memory check.
+static INPUT_BYTES: AtomicUsize = AtomicUsize::new(0);
+/// Input length in elements of the cases being registered, for the memory
+/// check.
+static INPUT_ELEMENTS: AtomicUsize = AtomicUsize::new(0);
+/// Set when the bench binary is started with `--dhat`. Cases are then run once
+/// under the dhat heap profiler instead of being measured.
+#[cfg(feature = "dhat")]
+static DHAT: AtomicBool = AtomicBool::new(false);
+static SCHEDULE: Mutex<Schedule> = Mutex::new(Schedule { cases: Vec::new() });
+/// Name of the group the cases being registered belong to, see [`new_group`].
+static GROUP: Mutex<String> = Mutex::new(String::new());
+/// Sampling of the cases being registered, for the dry run's estimates.
+static SAMPLING: Mutex<Sampling> = Mutex::new(Sampling::DEFAULT);
+/// Filter of the benchmark IDs given on the command line, which criterion
+/// doesn't parse in a dry run.
+static FILTER: OnceLock<Option<Regex>> = OnceLock::new();
+
+/// Expected sum of the input of the case being registered, if sums are
+/// verified. Set as the case takes its input, by [`expect`], [`Input::get`] or
+/// [`create_checked_data`], and taken by the case as it is registered, so that
+/// a case that doesn't set one can't inherit that of the case before it.
+static EXPECTED: Mutex<Option<Expected>> = Mutex::new(None);
+
+/// An input shared by several cases, along with the expected sum of the values
+/// it was created from if sums are verified. Each case takes the input with
+/// [`Input::get`], which records that sum as its own.
+struct Input<T> {
+    data: T,
+    expected: Option<Expected>,
+}
+
+impl<T> Input<T> {
+    /// Collect `values` into the input, expecting the sum of the values as
+    /// they were before they went into the container.
+    fn of<V, P>(values: Vec<V>) -> Self
+    where
+        V: Element<P>,
+        P: Primitive,
+        T: iter::FromIterator<V>,
+    {
+        let expected = if verify_sums() {
+            Some(Expected::of(&values))
+        } else {
+            None
+        };
+
+        Input {
+            data: values.into_iter().collect(),
+            expected,
+        }
+    }
+
+    /// Share `data`, expected to sum to what `expected` computes if sums are
+    /// verified, for the inputs the kernels don't sum the squares of as they
+    /// are.
+    fn with_sum(data: T, expected: impl FnOnce(&T) -> Expected) -> Self {
+        let expected = if verify_sums() {
+            Some(expected(&data))
+        } else {
+            None
+        };
+
+        Input { data, expected }
+    }
+
+    /// The input, recording its expected sum as that of the case being
+    /// registered.
+    fn get(&self) -> &T {
+        if let Some(expected) = self.expected {
+            expect_sum(expected);
+        }
+
+        &self.data
+    }
+
+    /// Another container of the same values, built from this one, and so
+    /// expected to sum to the same.
+    #[cfg(any(feature = "ndarray", feature = "nalgebra", feature = "arrow"))]
+    fn map<U>(&self, f: impl FnOnce(&T) -> U) -> Input<U> {
+        Input {
+            data: f(&self.data),
+            expected: self.expected,
+        }
+    }
+}
+
+/// Collect `values` into the input of the case being registered, recording the
+/// expected sum of the values before they went into the container, if sums are
+/// verified.
+fn expect<V, T, P>(values: Vec<V>) -> T
+where
+    V: Element<P>,
+    P: Primitive,
+    T: iter::FromIterator<V>,
+{
+    let input = Input::of(values);
+    input.get();
+    input.data
+}
+
+/// Record the expected sum of `values`, the values an input holds along with
+/// others, if sums are verified.
+fn expect_values<'a, V, P>(values: impl IntoIterator<Item = &'a V>)
+where
+    V: Element<P> + 'a,
+    P: Primitive,
+{
+    if verify_sums() {
+        expect_sum(Expected::of(values));
+    }
+}
+
+/// Like [`expect`], for byte inputs, which the kernels sum exactly in `u64`.
+fn expect_bytes<T>(values: Vec<u8>) -> T
+where
+    T: iter::FromIterator<u8>,
+{
+    if verify_sums() {
+        let sum = values.iter().map(|&x| i128::from(x) * i128::from(x)).sum();
+        expect_sum(Expected::Int(sum));
+    }
+
+    values.into_iter().collect()
+}
+
+/// Record `expected` as the sum of the case being registered, for the cases
+/// whose routine doesn't sum the squares of their input as it is.
+fn expect_sum(expected: Expected) {
+    *EXPECTED.lock().unwrap() = Some(expected);
+}
+
+/// The sums the first and the last measured iteration of a case returned,
+/// checked against the expected sum of its input once the case has been
+/// measured, if sums are verified.
+struct SumCheck<'a> {
+    ds_name: &'a str,
+    parameter_name: &'a str,
+    expected: Option<Expected>,
+    verify: bool,
+    first: Option<checksum::Sum>,
+    last: Option<checksum::Sum>,
+}
+
+impl<'a> SumCheck<'a> {
+    /// Check the case against the expected sum recorded for it, if any, which
+    /// the case takes so that the next one starts without.
+    fn new(ds_name: &'a str, parameter_name: &'a str) -> Self {
+        SumCheck {
+            ds_name,
+            parameter_name,
+            expected: EXPECTED.lock().unwrap().take(),
+            verify: verify_sums(),
+            first: None,
+            last: None,
+        }
+    }
+
+    /// Keep the sum of `output` as the last, and the first if it is.
+    fn observe<O: Checked>(&mut self, output: O) -> O {
+        if self.verify {
+            let sum = output.sum();
+            if self.first.is_none() {
+                self.first = sum;
+            }
+            self.last = sum;
+        }
+
+        output
+    }
+
+    /// Abort if the first or the last sum isn't the expected one. Routines
+    /// that don't return a sum aren't checked.
+    fn verify(self) {
+        if self.last.is_none() {
+            return;
+        }
+        let expected = self.expected.unwrap_or_else(|| {
+            eprintln!(
+                "error: no expected sum for {}",
+                case_id(self.ds_name, self.parameter_name)
+            );
+            process::exit(1);
+        });
+        for sum in self.first.iter().chain(self.last.iter()) {
+            if !expected.matches(*sum) {
+                sum_mismatch(self.ds_name, self.parameter_name, sum, expected);
+            }
+        }
+    }
+}
+
+/// Benchmark `routine` on a fresh input created by `setup` for every iteration.
+fn bench_batched<I, O, M>(
+    ds_name: &str,
+    parameter_name: &str,
+    group: &mut BenchmarkGroup<M>,
+    setup: impl FnMut() -> I,
+    routine: impl FnMut(I) -> O,
+) where
+    O: Checked,
+    M: Measurement,
+{
+    bench_batched_by(
+        ds_name,
+        parameter_name,
+        group,
+        BatchSize::LargeInput,
+        setup,
+        routine,
+    );
+}
+
+/// Like [`bench_batched`], with the inputs set up in batches of
+/// `batch_size`. Criterion runs every setup of a batch before its first
+/// iteration, so an input that the setup leaves cold needs
+/// `BatchSize::PerIteration`.
+fn bench_batched_by<I, O, M>(
+    ds_name: &str,
+    parameter_name: &str,
+    group: &mut BenchmarkGroup<M>,
+    batch_size: BatchSize,
+    mut setup: impl FnMut() -> I,
+    mut routine: impl FnMut(I) -> O,
+) where
+    O: Checked,
+    M: Measurement,
+{
+    // The expected sum is taken whether or not the case runs
+    let mut sums = SumCheck::new(ds_name, parameter_name);
+    if !selected(ds_name, parameter_name) {
+        return;
+    }
+    if DRY_RUN.load(Ordering::Relaxed) {
+        // The setup clones the input, and criterion keeps a batch of clones
+        let footprint = 2 * INPUT_BYTES.load(Ordering::Relaxed);
+        let iteration_time = probe(|| drop(routine(setup())));
+        record_probe(ds_name, parameter_name, iteration_time, footprint);
+        if cold_cache() {
+            let iteration_time = probe(|| {
+                let input = setup();
+                evict_caches();
+                drop(routine(input))
+            });
+            let footprint = footprint + evict_buffer_bytes();
+            record_probe(
+                &cold_name(ds_name),
+                parameter_name,
+                iteration_time,
+                footprint,
+            );
+        }
+        return;
+    }
+    #[cfg(feature = "dhat")]
+    if DHAT.load(Ordering::Relaxed) {
+        profile_allocations(ds_name, parameter_name, || drop(routine(setup())));
+        return;
+    }
+    check_thermal(ds_name, parameter_name);
+
+    let mut routine = |input| sums.observe(routine(input));
+    group.bench_function(BenchmarkId::new(ds_name, parameter_name), |b| {
+        b.iter_batched(&mut setup, &mut routine, batch_size)
+    });
+    if cold_cache() {
+        // A batch of inputs would warm up each other, so each iteration gets
+        // its own setup
+        let id = BenchmarkId::new(cold_name(ds_name), parameter_name);
+        group.bench_function(id, |b| {
+            b.iter_batched(
+                || {
+                    let input = setup();
+                    evict_caches();
+                    input
+                },
+                &mut routine,
+                BatchSize::PerIteration,
+            )
+        });
+    }
+    sums.verify();
+}
+
+/// Benchmark `routine` without any per-iteration setup.
+fn bench_iter<O, M>(
+    ds_name: &str,
+    parameter_name: &str,
+    group: &mut BenchmarkGroup<M>,
+    mut routine: impl FnMut() -> O,
+) where
+    O: Checked,
+    M: Measurement,
+{
+    // The expected sum is taken whether or not the case runs
+    let mut sums = SumCheck::new(ds_name, parameter_name);
+    if !selected(ds_name, parameter_name) {
+        return;
+    }
+    if DRY_RUN.load(Ordering::Relaxed) {
+        let footprint = INPUT_BYTES.load(Ordering::Relaxed);
+        let iteration_time = probe(|| drop(routine()));
+        record_probe(ds_name, parameter_name, iteration_time, footprint);
+        if cold_cache() {
+            let iteration_time = probe(|| {
+                evict_caches();
+                drop(routine())
+            });
+            let footprint = footprint + evict_buffer_bytes();
+            record_probe(
+                &cold_name(ds_name),
+                parameter_name,
+                iteration_time,
+                footprint,
+            );
+        }
+        return;
+    }
+    #[cfg(feature = "dhat")]
+    if DHAT.load(Ordering::Relaxed) {
+        profile_allocations(ds_name, parameter_name, || drop(routine()));
+        return;
     }
+    check_thermal(ds_name, parameter_name);
+
+    let mut routine = || sums.observe(routine());
+    group.bench_function(BenchmarkId::new(ds_name, parameter_name), |b| {
+        b.iter(&mut routine)
+    });
+    if cold_cache() {
+        let id = BenchmarkId::new(cold_name(ds_name), parameter_name);
+        group.bench_function(id, |b| {
+            b.iter_batched(evict_caches, |()| routine(), BatchSize::PerIteration)
+        });
+    }
+    sums.verify();
 }
 
-// Powers of 2u32 limits for measurements
-// 10 = 1 kB, 20 = 1 MB
-// 24 = 16 MB = L3 cache size on test platform
-// 26 = 64 MB = target
-// 30 = 1 GB
-const START_POW: u32 = 10;
-const END_POW: u32 = 26; // 26 for final measurements
-const STEP_POW: u32 = 2;
+/// Benchmark `routine` over the input `create` makes, which is only called
+/// once the case is selected, so that no input is created for the cases left
+/// out. Inputs shared by several cases are created on first use through a
+/// `LazyCell` that `create` dereferences.
+fn bench_iter_with<D, O, M>(
+    ds_name: &str,
+    parameter_name: &str,
+    group: &mut BenchmarkGroup<M>,
+    create: impl FnOnce() -> D,
+    mut routine: impl FnMut(&D) -> O,
+) where
+    O: Checked,
+    M: Measurement,
+{
+    if !admit(ds_name, parameter_name, 1) {
+        return;
+    }
 
-// Top level measurement organizers
+    let data = create();
+    bench_iter(ds_name, parameter_name, group, move || routine(&data));
+}
 
-fn bench_data_structures(c: &mut Criterion) {
-    compare_data_structures(START_POW, END_POW, STEP_POW, c);
+/// Like [`bench_iter_with`], for [`bench_batched`]: `setup` makes the input of
+/// each iteration from the input `create` makes once the case is selected.
+fn bench_batched_with<D, I, O, M>(
+    ds_name: &str,
+    parameter_name: &str,
+    group: &mut BenchmarkGroup<M>,
+    create: impl FnOnce() -> D,
+    mut setup: impl FnMut(&D) -> I,
+    routine: impl FnMut(I) -> O,
+) where
+    O: Checked,
+    M: Measurement,
+{
+    // The setup makes a copy of the input for each iteration
+    if !admit(ds_name, parameter_name, 2) {
+        return;
+    }
+
+    let data = create();
+    bench_batched(
+        ds_name,
+        parameter_name,
+        group,
+        move || setup(&data),
+        routine,
+    );
+}
+
+/// Set `SOS_ALLOCATIONS` to run each case of the container matrices on this many
+/// fresh allocations at different addresses, named as the case with an
+/// `[alloc i]` suffix.
+fn allocations() -> usize {
+    match env::var("SOS_ALLOCATIONS") {
+        Ok(n) => match n.parse() {
+            Ok(n) if n > 0 => n,
+            _ => {
+                eprintln!("error: invalid SOS_ALLOCATIONS: {}", n);
+                process::exit(1);
+            }
+        },
+        Err(_) => 1,
+    }
 }
 
-fn compare_data_structures(start_pow2: u32, end_pow2: u32, step_pow2: u32, c: &mut Criterion) {
-    let mut group = c.benchmark_group("Sum of squares");
+/// Register `case` once as `ds_name`, or once per allocation with its input
+/// displaced by a random block.
+fn replicate(ds_name: &str, mut case: impl FnMut(&str)) {
+    let n = allocations();
+    if n == 1 {
+        case(ds_name);
+        return;
+    }
 
-    let conf = PlotConfiguration::default().summary_scale(criterion::AxisScale::Logarithmic);
-    group.plot_config(conf);
-    // Force linear sampling mode for everything, the 1 MB+ samples will be a bit slow but that's fine
-    group.sampling_mode(criterion::SamplingMode::Linear);
+    for replica in 1..=n {
+        // Held until the case has been measured, as the case allocates its
+        // input when it is registered
+        let _displacement = replicas::Displacement::new(&mut rand::thread_rng());
+        case(&replicas::name(ds_name, replica));
+    }
+}
 
-    // Iterate over data-sizes of powers of two from START_POW to END_POW
-    let mut input_size_bytes = 2u32.pow(start_pow2) as usize;
-    while input_size_bytes <= 2u32.pow(end_pow2) as usize {
-        // Give input length in bytes to configure criterion
-        group.throughput(criterion::Throughput::Bytes(input_size_bytes as u64));
+/// Set `SOS_COLD_CACHE` to also run every case on an input evicted from the
+/// caches, named as the warm case with a `[cold]` suffix.
+fn cold_cache() -> bool {
+    env::var_os("SOS_COLD_CACHE").is_some()
+}
 
-        // A 64-bit float is 8 bytes long, so we divide 1024 by 8 bytes to obtain the
-        // right data length
-        let data_len = input_size_bytes / std::mem::size_of::<f64>();
-        let input_bytes_human = human_readable_size(input_size_bytes);
+fn cold_name(ds_name: &str) -> String {
+    format!("{} [cold]", ds_name)
+}
 
-        // Run all the benchmarks with this input size
-        bench_data_structures_in_group_with_input::<FloatOrd<f64>, _>(
-            &input_bytes_human,
-            data_len,
-            &mut group,
+/// Size of the buffer evicting the caches, for the detected last-level cache.
+fn evict_buffer_bytes() -> usize {
+    evict::buffer_bytes(&cache_capacities())
+}
+
+static EVICTOR: OnceLock<Evictor> = OnceLock::new();
+
+fn evict_caches() {
+    EVICTOR
+        .get_or_init(|| Evictor::new(evict_buffer_bytes()))
+        .evict();
+}
+
+/// Thermal monitor of the run, if `SOS_THERMAL` is set.
+static THERMAL: Mutex<Option<thermal::Monitor>> = Mutex::new(None);
+
+/// Set `SOS_THERMAL` to check for thermal throttling before every case, and to
+/// cool down when the CPU was throttled or reached the temperature in
+/// `SOS_THERMAL`, in °C, or 85 °C if it is empty.
+fn configure_thermal() {
+    let max_temp_c = match env::var("SOS_THERMAL") {
+        Ok(temp) if temp.is_empty() => thermal::DEFAULT_MAX_TEMP_C,
+        Ok(temp) => temp.parse().unwrap_or_else(|e| {
+            eprintln!("error: invalid SOS_THERMAL: {}: {}", temp, e);
+            process::exit(1);
+        }),
+        Err(_) => return,
+    };
+
+    metadata::record(metadata::RUN, "max_temp_c", max_temp_c);
+    *THERMAL.lock().unwrap() = Some(thermal::Monitor::new(max_temp_c));
+}
+
+/// Check for throttling since the previous case, cooling down if needed, and
+/// record the thermal state the case starts in.
+fn check_thermal(ds_name: &str, parameter_name: &str) {
+    let mut monitor = THERMAL.lock().unwrap();
+    let monitor = match monitor.as_mut() {
+        Some(monitor) => monitor,
+        None => return,
+    };
+
+    let id = case_id(ds_name, parameter_name);
+    let (sample, throttling) = monitor.check();
+    if let Some(throttling) = throttling {
+        eprintln!(
+            "warning: throttled before {} ({}), cooling down",
+            id, throttling
         );
+        let cooldown = monitor.cool_down();
+        metadata::record(&id, "throttled", throttling);
+        metadata::record(&id, "cooldown_s", cooldown.as_secs_f64());
+    }
+    if let Some(temp) = sample.max_temp_c {
+        metadata::record(&id, "temp_c", temp);
+    }
+    if let Some(fraction) = sample.freq_fraction {
+        metadata::record(&id, "freq_fraction", fraction);
+    }
+}
+
+/// Whether the case is selected by the groups, containers and kernels of the
+/// experiment, and by the filter on the command line, see [`filtered`]. Cases
+/// check this through [`admit`] before creating their input, so that no input
+/// is created for the cases left out.
+fn selected(ds_name: &str, parameter_name: &str) -> bool {
+    let experiment = experiment();
+    experiment.selects_group(&GROUP.lock().unwrap())
+        && experiment.selects(ds_name)
+        && (filtered(&case_id(ds_name, parameter_name))
+            || (cold_cache() && filtered(&case_id(&cold_name(ds_name), parameter_name))))
+}
 
-        input_size_bytes *= 2u32.pow(step_pow2) as usize;
+/// Whether the filter matches the benchmark `id`, as criterion's does, so that
+/// a filtered run doesn't create the inputs of the cases criterion skips.
+fn filtered(id: &str) -> bool {
+    FILTER
+        .get()
+        .and_then(Option::as_ref)
+        .is_none_or(|filter| filter.is_match(id))
+}
+
+// Options of criterion and of the libtest harness taking a value, which isn't
+// the filter
+const VALUE_OPTIONS: [&str; 17] = [
+    "-b",
+    "-c",
+    "-s",
+    "--baseline",
+    "--color",
+    "--confidence-level",
+    "--format",
+    "--load-baseline",
+    "--logfile",
+    "--measurement-time",
+    "--noise-threshold",
+    "--nresamples",
+    "--output-format",
+    "--plotting-backend",
+    "--profile-time",
+    "--sample-size",
+    "--save-baseline",
+];
+
+/// Parse the filter from the command line: the first argument that is neither
+/// an option nor the value of one, as criterion takes it.
+fn configure_filter() {
+    let mut args = env::args().skip(1);
+    let mut filter = None;
+    while let Some(arg) = args.next() {
+        if VALUE_OPTIONS.contains(&arg.as_str()) {
+            args.next();
+        } else if !arg.starts_with('-') {
+            filter = Some(arg);
+            break;
+        }
     }
 
-    group.finish();
+    let filter = filter.map(|filter| {
+        Regex::new(&filter).unwrap_or_else(|e| {
+            eprintln!("error: invalid filter: {}: {}", filter, e);
+            process::exit(1);
+        })
+    });
+    FILTER.set(filter).expect("the filter is configured once");
 }
 
-fn bench_data_structures_in_group_with_input<V, M>(
-    input_bytes_human: &str,
-    data_len: usize,
-    group: &mut BenchmarkGroup<M>,
-) where
-    V: Float<f64>,
-    M: Measurement,
-{
-    bench_by_ref_in_group::<V, Vec<V>, _>(
-        "Vec (by reference)",
-        &input_bytes_human,
-        data_len,
-        group,
-    );
-    bench_by_ref_in_group::<V, VecDeque<V>, _>(
-        "VecDeque (by reference)",
-        &input_bytes_human,
-        data_len,
-        group,
-    );
-    bench_by_ref_in_group::<V, LinkedList<V>, _>(
-        "LinkedList (by reference)",
-        &input_bytes_human,
-        data_len,
-        group,
-    );
-    bench_by_ref_in_group::<V, HashSet<V>, _>(
-        "HashSet (by reference)",
-        &input_bytes_human,
-        data_len,
-        group,
-    );
-    bench_by_ref_in_group::<V, BTreeSet<V>, _>(
-        "BTreeSet (by reference)",
-        &input_bytes_human,
-        data_len,
-        group,
-    );
+/// Whether to create the input of a case and register it: the case is selected
+/// and `copies` of its container over the input of the following cases, see
+/// [`configure_for_size`], fit in memory, see [`fits_in_memory`]. Every case
+/// checks this before it allocates its input. When only listing the cases, the
+/// case is recorded here instead, and no input is created.
+fn admit(ds_name: &str, parameter_name: &str, copies: usize) -> bool {
+    admit_input(
+        ds_name,
+        parameter_name,
+        INPUT_BYTES.load(Ordering::Relaxed),
+        INPUT_ELEMENTS.load(Ordering::Relaxed),
+        copies,
+    )
+}
 
-    bench_by_val_in_group::<V, Vec<V>, _>("Vec (by value)", &input_bytes_human, data_len, group);
-    bench_by_val_in_group::<V, VecDeque<V>, _>(
-        "VecDeque (by value)",
-        &input_bytes_human,
-        data_len,
-        group,
-    );
-    bench_by_val_in_group::<V, LinkedList<V>, _>(
-        "LinkedList (by value)",
-        &input_bytes_human,
-        data_len,
-        group,
-    );
-    bench_by_val_in_group::<V, HashSet<V>, _>(
-        "HashSet (by value)",
-        &input_bytes_human,
-        data_len,
-        group,
-    );
-    bench_by_val_in_group::<V, BTreeSet<V>, _>(
-        "BTreeSet (by value)",
-        &input_bytes_human,
-        data_len,
-        group,
-    );
+/// Like [`admit`], for a case whose input takes `input_size_bytes` in
+/// `elements` elements rather than the input size of the group.
+fn admit_input(
+    ds_name: &str,
+    parameter_name: &str,
+    input_size_bytes: usize,
+    elements: usize,
+    copies: usize,
+) -> bool {
+    if !selected(ds_name, parameter_name) {
+        return false;
+    }
+    let footprint = copies * memory::container_bytes(ds_name, input_size_bytes, elements);
+    if !fits_in_memory(ds_name, parameter_name, footprint) {
+        return false;
+    }
+    if LIST.load(Ordering::Relaxed) {
+        record_listed(ds_name, parameter_name, footprint);
+        return false;
+    }
+
+    true
+}
+
+/// Set `SOS_VERIFY_SUMS` to check that the kernels of the main matrices compute
+/// the expected sum over each input before measuring them, that the first and
+/// the last measured iteration of every case return the expected sum of its
+/// input, and to abort if they don't.
+fn verify_sums() -> bool {
+    env::var_os("SOS_VERIFY_SUMS").is_some()
 }
 
-fn bench_by_ref_in_group<V, T, M>(
+/// Create the input of a case, checking `kernel` over it if sums are verified.
+///
+/// The values are distinct, so that every container holds all `data_len` of
+/// them and the sets sum the same values as the sequences.
+fn create_checked_data<V, T, P>(
     ds_name: &str,
     parameter_name: &str,
     data_len: usize,
-    group: &mut BenchmarkGroup<M>,
-) where
-    V: Float<f64>,
-    T: iter::FromIterator<V> + iter::IntoIterator<Item = V> + Clone,
+    kernel: impl FnOnce(&T) -> P,
+) -> T
+where
+    V: Element<P>,
+    P: Primitive,
+    T: iter::FromIterator<V>,
     for<'a> &'a T: iter::IntoIterator<Item = &'a V>,
-    M: Measurement,
 {
-    // Create concrete data-structure using FromIterator<V>
-    let data: T = create_scrambled_data(data_len);
+    if !verify_sums() {
+        let data = create_distinct_data::<V, T, P>(data_len);
+        check_len(ds_name, parameter_name, &data, data_len);
+        return data;
+    }
 
-    group.bench_function(BenchmarkId::new(ds_name, parameter_name), move |b| {
-        b.iter_batched(
-            || data.clone(),
-            |data| sum_of_squares_by_ref(black_box(&data)),
-            BatchSize::LargeInput,
-        )
-    });
+    let values: Vec<V> = create_distinct_data(data_len);
+    let data = values.iter().copied().collect::<T>();
+    check_len(ds_name, parameter_name, &data, data_len);
+    let expected = Expected::of(&values);
+    let actual = kernel(&data).to_f64().unwrap_or(f64::NAN);
+    if !expected.matches(checksum::Sum::Float(actual)) {
+        sum_mismatch(ds_name, parameter_name, actual, expected);
+    }
+    expect_sum(expected);
+
+    data
 }
 
-fn bench_by_val_in_group<V, T, M>(
+/// Create the input of an integer case, checking `kernel` over it if sums are
+/// verified. Like [`create_checked_data`], the values are distinct.
+fn create_checked_ints<V, T, P>(
     ds_name: &str,
     parameter_name: &str,
     data_len: usize,
-    group: &mut BenchmarkGroup<M>,
-) where
-    V: Float<f64>,
-    T: iter::FromIterator<V> + iter::IntoIterator<Item = V> + Clone + iter::IntoIterator<Item = V>,
-    M: Measurement,
+    kernel: impl FnOnce(&T) -> P,
+) -> T
+where
+    V: Int<P>,
+    P: IntPrimitive,
+    T: iter::FromIterator<V>,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a V>,
+    rand::distributions::Standard: rand::distributions::Distribution<P>,
 {
-    // Create concrete data-structure using FromIterator<V>
-    let data: T = create_scrambled_data(data_len);
+    if !verify_sums() {
+        let data = create_distinct_ints::<V, T, P>(data_len);
+        check_len(ds_name, parameter_name, &data, data_len);
+        return data;
+    }
 
-    group.bench_function(BenchmarkId::new(ds_name, parameter_name), move |b| {
-        b.iter_batched(
-            || data.clone(),
-            |data| sum_of_squares_by_move(black_box(data)),
-            BatchSize::LargeInput,
-        )
-    });
+    let values: Vec<V> = create_distinct_ints(data_len);
+    let data = values.iter().copied().collect::<T>();
+    check_len(ds_name, parameter_name, &data, data_len);
+    let expected = Expected::of_ints(&values);
+    match kernel(&data).to_i128() {
+        Some(actual) if expected.matches(checksum::Sum::Int(actual)) => {}
+        Some(actual) => sum_mismatch(ds_name, parameter_name, actual, expected),
+        // Only a `u128` sum can be out of range, and no expected sum is
+        None => sum_mismatch(ds_name, parameter_name, "over i128::MAX", expected),
+    }
+    expect_sum(expected);
+
+    data
 }
 
-/// Create the concrete data-structure of length `n` using FromIterator<V> where V is the element type.
-fn create_scrambled_data<V, T>(n: usize) -> T
+/// Abort if the container of a case doesn't hold all `data_len` values, as a
+/// set would if any of them repeated.
+fn check_len<T>(ds_name: &str, parameter_name: &str, data: &T, data_len: usize)
 where
-    V: Float<f64>,
-    T: FromIterator<V>,
+    for<'a> &'a T: iter::IntoIterator,
 {
-    let mut rng = rand::thread_rng();
+    let len = data.into_iter().count();
+    if len != data_len {
+        eprintln!(
+            "error: {} holds {} values instead of {}",
+            case_id(ds_name, parameter_name),
+            len,
+            data_len
+        );
+        process::exit(1);
+    }
+}
+
+fn sum_mismatch(
+    ds_name: &str,
+    parameter_name: &str,
+    actual: impl std::fmt::Display,
+    expected: impl std::fmt::Display,
+) -> ! {
+    eprintln!(
+        "error: {} computed a sum of {}, expected {}",
+        case_id(ds_name, parameter_name),
+        actual,
+        expected
+    );
+    process::exit(1);
+}
+
+/// Whether the `footprint` of a case fits in the share of the available memory
+/// in `SOS_MEMORY_FRACTION`, 80 % by default. The by-value cases hold two
+/// copies of their container, the input and the clone their setup makes. Cases
+/// that don't fit are skipped with a warning and recorded as skipped.
+fn fits_in_memory(ds_name: &str, parameter_name: &str, footprint: usize) -> bool {
+    let available = match memory::available_bytes() {
+        Ok(available) => available,
+        Err(_) => return true,
+    };
+    let limit = (available as f64 * memory_fraction()) as usize;
+    if footprint <= limit {
+        return true;
+    }
+
+    let id = case_id(ds_name, parameter_name);
+    eprintln!(
+        "warning: skipping {}: needs about {} of {} available",
+        id,
+        human_readable_size(footprint),
+        human_readable_size(available)
+    );
+    if !DRY_RUN.load(Ordering::Relaxed) {
+        metadata::record(&id, "skipped_footprint_bytes", footprint);
+        metadata::record(&id, "skipped_available_bytes", available);
+    }
+    false
+}
+
+fn memory_fraction() -> f64 {
+    match env::var("SOS_MEMORY_FRACTION") {
+        Ok(fraction) => match fraction.parse::<f64>() {
+            Ok(fraction) if fraction > 0.0 && fraction <= 1.0 => fraction,
+            _ => {
+                eprintln!("error: invalid SOS_MEMORY_FRACTION: {}", fraction);
+                process::exit(1);
+            }
+        },
+        Err(_) => memory::DEFAULT_MAX_FRACTION,
+    }
+}
+
+/// Run `iteration` repeatedly for the probe time and return its mean duration.
+fn probe(mut iteration: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    let mut iterations = 0u32;
+    while iterations == 0 || start.elapsed() < schedule::PROBE_TIME {
+        iteration();
+        iterations += 1;
+    }
+    start.elapsed() / iterations
+}
+
+/// Run `iteration` once under the heap profiler and record its allocations.
+///
+/// Only allocations made by the iteration itself are counted: the input of
+/// cases without a per-iteration setup is allocated before profiling starts.
+#[cfg(feature = "dhat")]
+fn profile_allocations(ds_name: &str, parameter_name: &str, iteration: impl FnOnce()) {
+    let profiler = dhat::Profiler::builder().testing().build();
+    iteration();
+    let stats = dhat::HeapStats::get();
+    drop(profiler);
+
+    let id = case_id(ds_name, parameter_name);
+    metadata::record(&id, "alloc_blocks", stats.total_blocks);
+    metadata::record(&id, "alloc_bytes", stats.total_bytes);
+    metadata::record(&id, "peak_heap_bytes", stats.max_bytes);
+}
+
+/// With `SOS_VERIFY_RESIDENCY` set, check that the pages holding the input are
+/// resident in memory before measuring, and record the resident fraction.
+fn verify_residency<'a, V: 'a>(
+    ds_name: &str,
+    parameter_name: &str,
+    elements: impl IntoIterator<Item = &'a V>,
+) {
+    if DRY_RUN.load(Ordering::Relaxed) || env::var_os("SOS_VERIFY_RESIDENCY").is_none() {
+        return;
+    }
+
+    let id = case_id(ds_name, parameter_name);
+    match residency::resident_fraction(elements) {
+        Some(fraction) => {
+            if fraction < 1.0 {
+                eprintln!(
+                    "warning: only {:.1} % of the input of {} is resident",
+                    fraction * 100.0,
+                    id
+                );
+            }
+            metadata::record(&id, "resident_fraction", fraction);
+        }
+        None => eprintln!("warning: could not verify residency of the input of {}", id),
+    }
+}
+
+fn record_probe(ds_name: &str, parameter_name: &str, iteration_time: Duration, footprint: usize) {
+    let id = case_id(ds_name, parameter_name);
+    if !filtered(&id) {
+        return;
+    }
+    let sampling = *SAMPLING.lock().unwrap();
+    SCHEDULE
+        .lock()
+        .unwrap()
+        .push(id, iteration_time, sampling, footprint);
+}
+
+/// The criterion ID of a case of the group being registered,
+/// `group/function/parameter`.
+fn case_id(ds_name: &str, parameter_name: &str) -> String {
+    format!("{}/{}/{}", GROUP.lock().unwrap(), ds_name, parameter_name)
+}
 
-    (0..n).into_iter().map(|_| V::create(rng.gen())).collect()
+/// Record a case without running it, along with its cold variant.
+fn record_listed(ds_name: &str, parameter_name: &str, footprint: usize) {
+    let iteration_time = schedule::modelled_iteration_time(footprint);
+    record_probe(ds_name, parameter_name, iteration_time, footprint);
+    if cold_cache() {
+        let footprint = footprint + evict_buffer_bytes();
+        let iteration_time = schedule::modelled_iteration_time(footprint);
+        record_probe(
+            &cold_name(ds_name),
+            parameter_name,
+            iteration_time,
+            footprint,
+        );
+    }
 }
 
 // Final data loop used by everything
 
-fn sum_of_squares_by_ref<V, T>(collection: &T) -> f64
+fn sum_of_squares_by_ref<V, T, P>(collection: &T) -> P
 where
-    V: Float<f64>,
+    T: ?Sized,
+    V: Element<P>,
+    P: Primitive,
     for<'a> &'a T: iter::IntoIterator<Item = &'a V>,
 {
     spp_experiments::sum_of_squares_by_ref(collection)
 }
 
-fn sum_of_squares_by_move<V, T>(collection: T) -> f64
+fn sum_of_squares_by_move<V, T, P>(collection: T) -> P
 where
-    V: Float<f64>,
+    V: Element<P>,
+    P: Primitive,
     T: iter::IntoIterator<Item = V>,
 {
     spp_experiments::sum_of_squares_by_move(collection)
@@ -211,5 +4968,345 @@ where
 
 // Criterion setup
 
-criterion_group!(benches, bench_data_structures);
-criterion_main!(benches);
+/// Every top level measurement organizer, in the order they run.
+fn all_benches<M: Measurement>() -> Vec<fn(&mut Criterion<M>)> {
+    let benches: &[fn(&mut Criterion<M>)] = &[
+        bench_data_structures,
+        bench_clones,
+        bench_construction,
+        bench_drops,
+        bench_traversal,
+        bench_f32_data_structures,
+        bench_i32_data_structures,
+        bench_i64_data_structures,
+        bench_stack_arrays,
+        bench_compute_ceiling,
+        bench_maps,
+        bench_hashers,
+        bench_hash_collisions,
+        bench_load_factors,
+        bench_indirection,
+        bench_node_sizes,
+        bench_slab_occupancy,
+        bench_slotmaps,
+        bench_shared_slices,
+        bench_deque_layouts,
+        bench_ordered_sets,
+        bench_row_lengths,
+        bench_record_layouts,
+        bench_padding,
+        bench_alignment,
+        bench_quantized,
+        bench_mixed_precision,
+        bench_complex,
+        bench_byte_input,
+        bench_mmap,
+        bench_u8_data_structures,
+        bench_orders,
+        bench_distributions,
+        bench_non_finite,
+        bench_subnormals,
+        bench_wrapper,
+        bench_ordering_wrappers,
+        bench_slices,
+        bench_unrolling,
+        bench_styles,
+        bench_dispatch,
+        bench_indexing,
+        bench_block_sizes,
+        bench_rayon_styles,
+        bench_thread_counts,
+        #[cfg(target_arch = "x86_64")]
+        bench_x86_intrinsics,
+        #[cfg(feature = "ndarray")]
+        bench_ndarray,
+        #[cfg(feature = "nalgebra")]
+        bench_nalgebra,
+        #[cfg(feature = "arrow")]
+        bench_arrow,
+        #[cfg(feature = "half")]
+        bench_half_precision,
+        #[cfg(feature = "fixed")]
+        bench_fixed_point,
+        #[cfg(feature = "decimal")]
+        bench_decimal,
+        #[cfg(feature = "gpu")]
+        bench_gpu,
+        #[cfg(target_arch = "aarch64")]
+        bench_aarch64_intrinsics,
+    ];
+
+    let mut benches = benches.to_vec();
+    shuffle(&mut benches);
+
+    benches
+}
+
+/// Run every benchmark, measuring what `SOS_MEASUREMENT` selects: wall time by
+/// default, `tsc-cycles` on x86-64, with the `perf` feature `cache-misses`,
+/// `instructions`, `branch-misses`, `page-faults` or `dtlb-misses`, or with the
+/// `rapl` feature `energy`.
+fn benches() {
+    let measurement = measurement();
+    match measurement.as_str() {
+        results::WALL_TIME => run_benches(criterion()),
+        #[cfg(target_arch = "x86_64")]
+        "tsc-cycles" => run_benches(criterion().with_measurement(tsc::TscCycles::default())),
+        #[cfg(all(feature = "perf", target_os = "linux"))]
+        "cache-misses" => run_benches(
+            criterion().with_measurement(perf_counter(perf::PerfCounter::cache_misses())),
+        ),
+        #[cfg(all(feature = "perf", target_os = "linux"))]
+        "instructions" => run_benches(
+            criterion().with_measurement(perf_counter(perf::PerfCounter::instructions())),
+        ),
+        #[cfg(all(feature = "perf", target_os = "linux"))]
+        "branch-misses" => run_benches(
+            criterion().with_measurement(perf_counter(perf::PerfCounter::branch_misses())),
+        ),
+        #[cfg(all(feature = "perf", target_os = "linux"))]
+        "page-faults" => run_benches(
+            criterion().with_measurement(perf_counter(perf::PerfCounter::page_faults())),
+        ),
+        #[cfg(all(feature = "perf", target_os = "linux"))]
+        "dtlb-misses" => run_benches(
+            criterion().with_measurement(perf_counter(perf::PerfCounter::dtlb_misses())),
+        ),
+        #[cfg(all(feature = "rapl", target_os = "linux"))]
+        "energy" => {
+            let energy = rapl::RaplEnergy::new().unwrap_or_else(|e| {
+                eprintln!("error: could not read the RAPL energy counters: {}", e);
+                process::exit(1);
+            });
+            run_benches(criterion().with_measurement(energy))
+        }
+        _ => {
+            eprintln!(
+                "error: unknown measurement in SOS_MEASUREMENT: {}",
+                measurement
+            );
+            process::exit(1);
+        }
+    }
+    metadata::record(metadata::RUN, "measurement", measurement);
+}
+
+/// The measurement `SOS_MEASUREMENT` selects.
+fn measurement() -> String {
+    env::var("SOS_MEASUREMENT").unwrap_or_else(|_| results::WALL_TIME.to_string())
+}
+
+/// Whether the run writes the wall-time estimates that [`results::read_measured`]
+/// reads: not a dry run, a heap profile or a run measuring another counter.
+fn measures_wall_time() -> bool {
+    #[cfg(feature = "dhat")]
+    if DHAT.load(Ordering::Relaxed) {
+        return false;
+    }
+
+    !DRY_RUN.load(Ordering::Relaxed) && measurement() == results::WALL_TIME
+}
+
+/// Criterion writing its results to the directory of the measurement, apart
+/// from the wall-time results the tools read.
+fn criterion() -> Criterion {
+    let measurement = measurement();
+    if measurement == results::WALL_TIME {
+        Criterion::default()
+    } else {
+        Criterion::default().output_directory(&results::measurement_dir(&measurement))
+    }
+}
+
+fn run_benches<M: Measurement>(c: Criterion<M>) {
+    // With the `pprof` feature, `--profile-time <seconds>` writes a flamegraph
+    // of each benchmark to its `profile` directory instead of measuring it
+    #[cfg(all(feature = "pprof", target_os = "linux"))]
+    let c = c.with_profiler(PProfProfiler::new(
+        PPROF_FREQUENCY,
+        Output::Flamegraph(None),
+    ));
+    // Arguments given on the command line take precedence over the experiment
+    let mut c = configure_quick(configure_from_experiment(c)).configure_from_args();
+    // Criterion turns the plots back on unless `--noplot` is given
+    if quick() {
+        c = c.without_plots();
+    }
+    for bench in all_benches() {
+        bench(&mut c);
+    }
+}
+
+/// Apply the criterion settings and group selection of the experiment.
+fn configure_from_experiment<M: Measurement>(mut c: Criterion<M>) -> Criterion<M> {
+    let experiment = experiment();
+    if let Some(sample_size) = experiment.sample_size {
+        c = c.sample_size(sample_size);
+    }
+    if let Some(measurement_time) = experiment.measurement_time {
+        c = c.measurement_time(measurement_time);
+    }
+    if let Some(warm_up_time) = experiment.warm_up_time {
+        c = c.warm_up_time(warm_up_time);
+    }
+    if let Some(filter) = experiment.group_filter() {
+        c = c.with_filter(filter);
+    }
+
+    c
+}
+
+/// In quick mode, measure as briefly as criterion allows, bootstrap fewer
+/// resamples and skip the plots, which [`run_benches`] skips again after the
+/// command line.
+fn configure_quick<M: Measurement>(c: Criterion<M>) -> Criterion<M> {
+    if !quick() {
+        return c;
+    }
+
+    c.sample_size(QUICK_SAMPLE_SIZE)
+        .warm_up_time(QUICK_WARM_UP_TIME)
+        .measurement_time(QUICK_MEASUREMENT_TIME)
+        .nresamples(QUICK_RESAMPLES)
+        .without_plots()
+}
+
+/// Sampling frequency of the profiler in Hz, a prime so that the samples
+/// don't fall in step with the loop iterations.
+#[cfg(all(feature = "pprof", target_os = "linux"))]
+const PPROF_FREQUENCY: i32 = 997;
+
+#[cfg(all(feature = "perf", target_os = "linux"))]
+fn perf_counter(counter: std::io::Result<perf::PerfCounter>) -> perf::PerfCounter {
+    counter.unwrap_or_else(|e| {
+        eprintln!("error: could not open the performance counter: {}", e);
+        process::exit(1);
+    })
+}
+
+/// Record the frequency scaling settings and warn about those that make the
+/// measurements noisy, or abort on them if `SOS_REQUIRE_FIXED_FREQUENCY` is set.
+fn check_cpu_frequency() {
+    let state = match cpufreq::State::read() {
+        Ok(state) => state,
+        Err(e) => {
+            eprintln!(
+                "warning: could not read the frequency scaling settings: {}",
+                e
+            );
+            return;
+        }
+    };
+
+    if !state.governors.is_empty() {
+        metadata::record(metadata::RUN, "governor", state.governors.join(","));
+    }
+    if let Some(turbo) = state.turbo {
+        metadata::record(metadata::RUN, "turbo", turbo);
+    }
+
+    let issues = state.issues();
+    if issues.is_empty() {
+        return;
+    }
+    let strict = env::var_os("SOS_REQUIRE_FIXED_FREQUENCY").is_some();
+    for issue in &issues {
+        eprintln!("{}: {}", if strict { "error" } else { "warning" }, issue);
+    }
+    if strict {
+        eprintln!("error: unset SOS_REQUIRE_FIXED_FREQUENCY to benchmark anyway");
+        process::exit(1);
+    }
+}
+
+/// With `SOS_PHYSICAL_CORES` set, restrict all threads to one logical CPU per
+/// physical core so that SMT siblings don't share a core, and record the CPUs
+/// used.
+fn restrict_to_physical_cores() {
+    if env::var_os("SOS_PHYSICAL_CORES").is_none() {
+        return;
+    }
+
+    match topology::physical_core_cpus().and_then(|cpus| {
+        affinity::restrict_to(cpus.clone())?;
+        Ok(cpus)
+    }) {
+        Ok(cpus) => {
+            let cpus = cpus.iter().map(usize::to_string).collect::<Vec<_>>();
+            metadata::record(metadata::RUN, "physical_cores_only", cpus.join(","));
+        }
+        Err(e) => {
+            eprintln!("error: could not restrict to physical cores: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Pin the benchmark thread to the core given in `SOS_PIN_CORE`, if any, and
+/// record it.
+fn pin_to_configured_core() {
+    let core = match env::var("SOS_PIN_CORE") {
+        Ok(core) => core,
+        Err(_) => return,
+    };
+
+    match core.parse().map_err(|e| format!("{}", e)).and_then(|core| {
+        affinity::pin_current_thread(core)
+            .map(|_| core)
+            .map_err(|e| format!("{}", e))
+    }) {
+        Ok(core) => metadata::record(metadata::RUN, "pinned_core", core),
+        Err(e) => {
+            eprintln!("error: could not pin to core {}: {}", core, e);
+            process::exit(1);
+        }
+    }
+}
+
+fn main() {
+    // The global pool is started lazily, possibly after pinning, so its workers
+    // must unpin themselves like those of the explicitly built pools
+    parallel::unpinned_pool_builder()
+        .build_global()
+        .expect("could not configure the global thread pool");
+    restrict_to_physical_cores();
+    pin_to_configured_core();
+    load_experiment();
+    configure_sweep();
+    configure_shuffle();
+    configure_thermal();
+    configure_filter();
+
+    // Criterion rejects arguments it doesn't know, so the dry run is handled
+    // before it gets to parse them.
+    if env::args().any(|arg| arg == "--dry-run" || arg == "--list") {
+        DRY_RUN.store(true, Ordering::Relaxed);
+        LIST.store(env::args().any(|arg| arg == "--list"), Ordering::Relaxed);
+
+        // Configured as in a run, but for the command line, which holds the
+        // dry run's own arguments
+        let mut c = configure_quick(configure_from_experiment(Criterion::default()));
+        *SAMPLING.lock().unwrap() = uniform_sampling();
+        for bench in all_benches() {
+            bench(&mut c);
+        }
+
+        print!("{}", SCHEDULE.lock().unwrap());
+        return;
+    }
+    #[cfg(feature = "dhat")]
+    if env::args().any(|arg| arg == "--dhat") {
+        DHAT.store(true, Ordering::Relaxed);
+
+        let mut c = configure_quick(configure_from_experiment(Criterion::default()));
+        for bench in all_benches() {
+            bench(&mut c);
+        }
+        return;
+    }
+
+    check_cpu_frequency();
+    benches();
+
+    criterion().configure_from_args().final_summary();
+}
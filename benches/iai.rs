@@ -0,0 +1,102 @@
+//! Deterministic companion to the criterion suite: the main kernel × container
+//! matrix, run under Callgrind to count instructions and simulated cache
+//! misses instead of measuring wall time.
+//!
+//! Requires valgrind and the `iai-callgrind-runner` binary of the same version
+//! as the `iai-callgrind` dependency. Opt-in, so that a plain `cargo bench`
+//! only runs the criterion suite: `cargo bench --features iai --bench iai`.
+
+use float_ord::FloatOrd;
+use iai_callgrind::{black_box, library_benchmark, library_benchmark_group, main};
+use indexmap::IndexSet;
+use smallvec::SmallVec;
+use spp_experiments::{data::create_distinct_data, sum_of_squares_by_move, sum_of_squares_by_ref};
+use std::collections::{BTreeSet, BinaryHeap, HashSet, LinkedList, VecDeque};
+use std::iter;
+
+/// Create a container of `input_size_bytes` of distinct scrambled `f64`s.
+/// Only called in the benchmark arguments, outside of the counted region.
+fn setup<T>(input_size_bytes: usize) -> T
+where
+    T: iter::FromIterator<FloatOrd<f64>>,
+{
+    create_distinct_data(input_size_bytes / std::mem::size_of::<f64>())
+}
+
+// Callgrind is some 50x slower than native, so the sizes only cover L1, L2 and
+// beyond the last-level cache of the simulated machine
+macro_rules! container_benches {
+    ($($by_ref:ident, $by_val:ident: $container:ty;)+) => {
+        $(
+            #[library_benchmark]
+            #[bench::kb_1(setup(1024))]
+            #[bench::kb_64(setup(64 * 1024))]
+            #[bench::mb_16(setup(16 * 1024 * 1024))]
+            // The container goes back out with the sum so that its drop is
+            // not counted against the by-reference kernel
+            fn $by_ref(data: $container) -> (f64, $container) {
+                let sum = black_box(sum_of_squares_by_ref(black_box(&data)));
+                (sum, data)
+            }
+
+            #[library_benchmark]
+            #[bench::kb_1(setup(1024))]
+            #[bench::kb_64(setup(64 * 1024))]
+            #[bench::mb_16(setup(16 * 1024 * 1024))]
+            fn $by_val(data: $container) -> f64 {
+                black_box(sum_of_squares_by_move(black_box(data)))
+            }
+        )+
+    };
+}
+
+container_benches! {
+    vec_by_ref, vec_by_val: Vec<FloatOrd<f64>>;
+    boxed_slice_by_ref, boxed_slice_by_val: Box<[FloatOrd<f64>]>;
+    smallvec_16_by_ref, smallvec_16_by_val: SmallVec<[FloatOrd<f64>; 16]>;
+    smallvec_1024_by_ref, smallvec_1024_by_val: SmallVec<[FloatOrd<f64>; 1024]>;
+    vec_deque_by_ref, vec_deque_by_val: VecDeque<FloatOrd<f64>>;
+    im_vector_by_ref, im_vector_by_val: im::Vector<FloatOrd<f64>>;
+    linked_list_by_ref, linked_list_by_val: LinkedList<FloatOrd<f64>>;
+    binary_heap_by_ref, binary_heap_by_val: BinaryHeap<FloatOrd<f64>>;
+    hash_set_by_ref, hash_set_by_val: HashSet<FloatOrd<f64>>;
+    hashbrown_hash_set_by_ref, hashbrown_hash_set_by_val: hashbrown::HashSet<FloatOrd<f64>>;
+    index_set_by_ref, index_set_by_val: IndexSet<FloatOrd<f64>>;
+    btree_set_by_ref, btree_set_by_val: BTreeSet<FloatOrd<f64>>;
+}
+
+library_benchmark_group!(
+    name = by_reference;
+    benchmarks =
+        vec_by_ref,
+        boxed_slice_by_ref,
+        smallvec_16_by_ref,
+        smallvec_1024_by_ref,
+        vec_deque_by_ref,
+        im_vector_by_ref,
+        linked_list_by_ref,
+        binary_heap_by_ref,
+        hash_set_by_ref,
+        hashbrown_hash_set_by_ref,
+        index_set_by_ref,
+        btree_set_by_ref
+);
+
+library_benchmark_group!(
+    name = by_value;
+    benchmarks =
+        vec_by_val,
+        boxed_slice_by_val,
+        smallvec_16_by_val,
+        smallvec_1024_by_val,
+        vec_deque_by_val,
+        im_vector_by_val,
+        linked_list_by_val,
+        binary_heap_by_val,
+        hash_set_by_val,
+        hashbrown_hash_set_by_val,
+        index_set_by_val,
+        btree_set_by_val
+);
+
+main!(library_benchmark_groups = by_reference, by_value);
@@ -1 +1,3 @@
-cargo asm spp_experiments_bin::main --rust
+# Disassemble a kernel of the library, `sum_of_squares_slice_f64` unless another
+# is named, e.g. `./disasm.sh sum_of_squares_slice_f32`
+cargo asm "spp_experiments::${1:-sum_of_squares_slice_f64}" --rust
@@ -0,0 +1,43 @@
+//! Hand-written aarch64 NEON kernels.
+//!
+//! NEON is part of the aarch64 baseline, but the kernel is still gated on
+//! runtime detection to mirror the x86-64 kernels.
+
+use std::arch::aarch64::*;
+
+/// Whether the NEON kernel can run on this CPU.
+pub fn neon_available() -> bool {
+    std::arch::is_aarch64_feature_detected!("neon")
+}
+
+/// Sum the square of each value with 128-bit NEON fused multiply-adds.
+///
+/// # Panics
+///
+/// If the CPU doesn't support NEON, see [`neon_available`].
+pub fn sum_of_squares_neon(data: &[f64]) -> f64 {
+    assert!(neon_available(), "NEON is not supported on this CPU");
+
+    // SAFETY: the required target feature was detected above
+    unsafe { sum_of_squares_neon_fma(data) }
+}
+
+#[target_feature(enable = "neon")]
+unsafe fn sum_of_squares_neon_fma(data: &[f64]) -> f64 {
+    const LANES: usize = 2;
+
+    let chunks = data.chunks_exact(4 * LANES);
+    let tail = chunks.remainder().iter().map(|x| x.powi(2)).sum::<f64>();
+
+    let mut sums = [vdupq_n_f64(0.0); 4];
+    for chunk in chunks {
+        for (i, sum) in sums.iter_mut().enumerate() {
+            let x = vld1q_f64(chunk.as_ptr().add(i * LANES));
+            *sum = vfmaq_f64(*sum, x, x);
+        }
+    }
+
+    let sum = vaddq_f64(vaddq_f64(sums[0], sums[1]), vaddq_f64(sums[2], sums[3]));
+
+    vaddvq_f64(sum) + tail
+}
@@ -0,0 +1,229 @@
+//! Numerical accuracy of the summation kernels.
+//!
+//! Every kernel is compared against an exact reference: the square of an `f64`
+//! is a dyadic rational, so the sum of squares can be accumulated without any
+//! rounding in a big integer scaled by a common power of two.
+
+use crate::{
+    data::{self, Distribution, Order},
+    sum_of_squares_by_ref, sum_of_squares_kahan, sum_of_squares_pairwise,
+};
+use float_ord::FloatOrd;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{Float as _, Signed, ToPrimitive, Zero};
+
+/// A summation kernel under test, operating on contiguous data.
+pub type Kernel = fn(&[FloatOrd<f64>]) -> f64;
+
+/// All summation variants the accuracy report covers, by display name.
+pub const KERNELS: &[(&str, Kernel)] = &[
+    ("naive", sum_of_squares_by_ref),
+    ("kahan", sum_of_squares_kahan),
+    ("pairwise", sum_of_squares_pairwise),
+    #[cfg(feature = "simd")]
+    ("simd", crate::simd::sum_of_squares_simd),
+];
+
+/// Error of a single kernel on a single input.
+#[derive(Clone, Debug)]
+pub struct KernelError {
+    pub kernel: &'static str,
+    pub value: f64,
+    pub abs_error: f64,
+    pub rel_error: f64,
+}
+
+/// Error of every kernel on one input size, distribution and order.
+#[derive(Clone, Debug)]
+pub struct AccuracyReport {
+    pub input_size_bytes: usize,
+    pub distribution: Distribution,
+    pub order: Order,
+    pub reference: f64,
+    pub errors: Vec<KernelError>,
+}
+
+/// Compute the exact sum of squares of `values`.
+///
+/// Non-finite inputs have no exact sum, so they must not be present.
+pub fn reference_sum_of_squares(values: &[f64]) -> BigRational {
+    // x = m * 2^e, so x^2 = m^2 * 2^(2e). Scale every square to the smallest
+    // exponent in the input and sum the integer mantissas.
+    let decoded = values
+        .iter()
+        .inspect(|x| assert!(x.is_finite(), "no exact sum for {}", x))
+        .map(|x| x.integer_decode())
+        .collect::<Vec<_>>();
+    let min_exp = decoded
+        .iter()
+        .map(|&(_, exp, _)| 2 * i32::from(exp))
+        .min()
+        .unwrap_or(0);
+
+    let mut sum = BigInt::zero();
+    for &(mantissa, exp, _) in &decoded {
+        let square = BigInt::from(mantissa) * BigInt::from(mantissa);
+        sum += square << (2 * i32::from(exp) - min_exp) as usize;
+    }
+
+    if min_exp < 0 {
+        BigRational::new(sum, BigInt::from(1) << (-min_exp) as usize)
+    } else {
+        BigRational::from_integer(sum << min_exp as usize)
+    }
+}
+
+/// Bound on the relative error of any kernel summing the squares of `len`
+/// values in a type with unit roundoff `unit_roundoff`, half its machine
+/// epsilon.
+///
+/// The squares are all non-negative, so rounding each of them and each of the
+/// additions, in whatever order the kernel adds them, stays within
+/// γ(len) = len·u / (1 − len·u) of the exact sum. The compensated and pairwise
+/// kernels stay well within it. The bound is infinite once len·u reaches 1.
+pub fn error_bound(len: usize, unit_roundoff: f64) -> f64 {
+    let nu = len.max(1) as f64 * unit_roundoff;
+    if nu < 1.0 {
+        nu / (1.0 - nu)
+    } else {
+        f64::INFINITY
+    }
+}
+
+/// Measure the error of each of [`KERNELS`] on `data` against the exact sum.
+pub fn kernel_errors(data: &[FloatOrd<f64>]) -> (f64, Vec<KernelError>) {
+    let values = data.iter().map(|x| x.0).collect::<Vec<_>>();
+    let reference = reference_sum_of_squares(&values);
+
+    let errors = KERNELS
+        .iter()
+        .map(|&(kernel, f)| {
+            let value = f(data);
+            let abs_error = match BigRational::from_float(value) {
+                Some(value) => (value - &reference).abs(),
+                None => return error_of_non_finite(kernel, value),
+            };
+            let rel_error = if reference.is_zero() {
+                0.0
+            } else {
+                (&abs_error / &reference).to_f64().unwrap_or(f64::INFINITY)
+            };
+            KernelError {
+                kernel,
+                value,
+                abs_error: abs_error.to_f64().unwrap_or(f64::INFINITY),
+                rel_error,
+            }
+        })
+        .collect();
+
+    (reference.to_f64().unwrap_or(f64::INFINITY), errors)
+}
+
+fn error_of_non_finite(kernel: &'static str, value: f64) -> KernelError {
+    KernelError {
+        kernel,
+        value,
+        abs_error: f64::INFINITY,
+        rel_error: f64::INFINITY,
+    }
+}
+
+/// Generate an input of `input_size_bytes` from `distribution` and measure the
+/// error of every kernel on it, once for each of the given orders.
+///
+/// All orders are permutations of the same values, so they share the exact
+/// reference and their errors can be compared directly.
+pub fn report(
+    input_size_bytes: usize,
+    distribution: Distribution,
+    orders: &[Order],
+) -> Vec<AccuracyReport> {
+    let data_len = input_size_bytes / std::mem::size_of::<f64>();
+    let scrambled: Vec<FloatOrd<f64>> = data::create_distinct_data_with(data_len, distribution);
+
+    orders
+        .iter()
+        .map(|&order| {
+            let mut data = scrambled.clone();
+            order.apply(&mut data);
+            let (reference, errors) = kernel_errors(&data);
+
+            AccuracyReport {
+                input_size_bytes,
+                distribution,
+                order,
+                reference,
+                errors,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pow2(exp: i32) -> BigRational {
+        let one = BigInt::from(1);
+        if exp < 0 {
+            BigRational::new(one.clone(), one << (-exp) as usize)
+        } else {
+            BigRational::from_integer(one << exp as usize)
+        }
+    }
+
+    #[test]
+    fn reference_of_small_integers_is_exact() {
+        assert_eq!(
+            reference_sum_of_squares(&[1.0, 2.0, 3.0, -4.0]),
+            BigRational::from_integer(BigInt::from(30))
+        );
+        assert!(reference_sum_of_squares(&[]).is_zero());
+    }
+
+    #[test]
+    fn reference_of_powers_of_two_is_exact() {
+        // 2^-40 + 2^60 is far beyond the precision of an f64
+        assert_eq!(
+            reference_sum_of_squares(&[2f64.powi(-20), 2f64.powi(30)]),
+            pow2(-40) + pow2(60)
+        );
+    }
+
+    #[test]
+    fn reference_of_subnormals_is_exact() {
+        // 2^-1074 and 3 * 2^-1074, whose squares underflow to zero in an f64
+        let smallest = f64::from_bits(1);
+        let three = f64::from_bits(3);
+        assert_eq!(
+            reference_sum_of_squares(&[smallest, three, 1.0]),
+            pow2(-2148) * BigInt::from(10) + pow2(0)
+        );
+    }
+
+    #[test]
+    fn compensated_and_pairwise_stay_within_the_bound() {
+        data::set_seed(data::DEFAULT_SEED);
+        let data: Vec<FloatOrd<f64>> =
+            data::create_distinct_data_with(1 << 14, Distribution::LogUniform);
+        let bound = error_bound(data.len(), f64::EPSILON / 2.0);
+
+        let (_, errors) = kernel_errors(&data);
+        let checked = errors
+            .iter()
+            .filter(|error| error.kernel == "kahan" || error.kernel == "pairwise")
+            .collect::<Vec<_>>();
+        assert_eq!(checked.len(), 2);
+        for error in checked {
+            assert!(
+                error.rel_error <= bound,
+                "{} has a relative error of {:e}, over the bound of {:e}",
+                error.kernel,
+                error.rel_error,
+                bound
+            );
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//! Pinning threads to CPU cores.
+//!
+//! The benchmarks run on the main thread, which is pinned to a single core so
+//! that migrations between cores don't add variance. Worker threads unpin
+//! themselves on start, since they would otherwise inherit the main thread's
+//! mask and share its one core.
+//!
+//! All threads can additionally be restricted to a subset of the cores, e.g.
+//! one logical CPU per physical core to keep SMT siblings out of the
+//! measurements.
+
+use std::{io, sync::Mutex};
+
+/// Cores that threads are restricted to, or `None` for every core.
+static ALLOWED_CORES: Mutex<Option<Vec<usize>>> = Mutex::new(None);
+
+/// Restrict the calling thread, and every thread that unpins itself
+/// afterwards, to `cores`.
+pub fn restrict_to(cores: Vec<usize>) -> io::Result<()> {
+    set_current_thread_affinity(&cores)?;
+    *ALLOWED_CORES.lock().unwrap() = Some(cores);
+
+    Ok(())
+}
+
+/// The cores threads are restricted to, or `None` if they may run on every
+/// core.
+pub fn allowed_cores() -> Option<Vec<usize>> {
+    ALLOWED_CORES.lock().unwrap().clone()
+}
+
+/// Restrict the calling thread to run only on `cores`.
+#[cfg(target_os = "linux")]
+pub fn set_current_thread_affinity(cores: &[usize]) -> io::Result<()> {
+    // SAFETY: cpu_set_t is plain data, for which all zeroes is the empty set
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    for &core in cores {
+        if core >= libc::CPU_SETSIZE as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("core {} is out of range", core),
+            ));
+        }
+        // SAFETY: the core index was checked to be in range above
+        unsafe { libc::CPU_SET(core, &mut set) };
+    }
+
+    // SAFETY: `set` is a valid, initialized cpu_set_t of the size given
+    let ret = unsafe { libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) };
+    if ret == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// Restrict the calling thread to run only on `cores`.
+///
+/// Affinity can only be set on Linux, so this always fails.
+#[cfg(not(target_os = "linux"))]
+pub fn set_current_thread_affinity(_cores: &[usize]) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "thread affinity is only supported on Linux",
+    ))
+}
+
+/// Pin the calling thread to a single core.
+pub fn pin_current_thread(core: usize) -> io::Result<()> {
+    set_current_thread_affinity(&[core])
+}
+
+/// Allow the calling thread to run on every allowed core again, see
+/// [`restrict_to`].
+///
+/// Without a restriction, the kernel intersects the mask with the cores the
+/// process is allowed to use, so every possible core can be requested.
+#[cfg(target_os = "linux")]
+pub fn unpin_current_thread() -> io::Result<()> {
+    let cores =
+        allowed_cores().unwrap_or_else(|| (0..libc::CPU_SETSIZE as usize).collect::<Vec<_>>());
+    set_current_thread_affinity(&cores)
+}
+
+/// Allow the calling thread to run on every core again.
+///
+/// Affinity can't be set outside of Linux, so there is nothing to undo.
+#[cfg(not(target_os = "linux"))]
+pub fn unpin_current_thread() -> io::Result<()> {
+    Ok(())
+}
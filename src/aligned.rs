@@ -0,0 +1,73 @@
+//! Input data in a buffer with explicit alignment.
+//!
+//! `Vec<f64>` only guarantees the 8-byte alignment of `f64`, and large
+//! allocations typically come page-aligned from the system allocator anyway.
+//! These buffers pin the start of the values to a chosen boundary, optionally
+//! offset from it.
+
+use std::{
+    alloc::{self, Layout},
+    mem, ptr, slice,
+};
+
+/// Cache line size assumed for the aligned buffers.
+pub const CACHE_LINE: usize = 64;
+
+/// Page size assumed for the aligned buffers.
+pub const PAGE: usize = 4096;
+
+/// An owned buffer of `f64`s starting `offset` bytes past an `align`-byte
+/// boundary.
+pub struct AlignedData {
+    base: *mut u8,
+    layout: Layout,
+    offset: usize,
+    len: usize,
+}
+
+impl AlignedData {
+    /// Copy `values` into a new buffer starting `offset` bytes past an
+    /// `align`-byte boundary.
+    ///
+    /// Panics if `align` is not a power of two, or if `align` or `offset` is
+    /// not a multiple of the alignment of `f64`.
+    pub fn new(values: &[f64], align: usize, offset: usize) -> Self {
+        assert!(align >= mem::align_of::<f64>());
+        assert_eq!(offset % mem::align_of::<f64>(), 0);
+        let size = offset + mem::size_of_val(values);
+        // Zero-sized allocations are not allowed
+        let layout = Layout::from_size_align(size.max(1), align).expect("invalid alignment");
+
+        // SAFETY: the layout has a non-zero size
+        let base = unsafe { alloc::alloc(layout) };
+        if base.is_null() {
+            alloc::handle_alloc_error(layout);
+        }
+        // SAFETY: the allocation holds `offset` bytes followed by the values,
+        // and the values start at a multiple of 8 bytes past a boundary of at
+        // least 8 bytes
+        unsafe {
+            ptr::copy_nonoverlapping(values.as_ptr(), base.add(offset) as *mut f64, values.len());
+        }
+
+        AlignedData {
+            base,
+            layout,
+            offset,
+            len: values.len(),
+        }
+    }
+
+    pub fn as_slice(&self) -> &[f64] {
+        // SAFETY: the values were initialized in `new` and live as long as
+        // `self`
+        unsafe { slice::from_raw_parts(self.base.add(self.offset) as *const f64, self.len) }
+    }
+}
+
+impl Drop for AlignedData {
+    fn drop(&mut self) {
+        // SAFETY: `base` was allocated with `layout` in `new`
+        unsafe { alloc::dealloc(self.base, self.layout) };
+    }
+}
@@ -0,0 +1,49 @@
+//! Achieved memory bandwidth of the measured results, against the peak of a
+//! STREAM-like triad.
+
+use criterion::black_box;
+use std::time::{Duration, Instant};
+
+/// Length of each triad array: 3 × 64 MB, well beyond any last-level cache.
+const TRIAD_LEN: usize = 8 * 1024 * 1024;
+/// The fastest of this many triads is taken as the peak.
+const TRIAD_REPEATS: usize = 10;
+
+/// Results reaching this fraction of the peak are considered bandwidth-bound.
+pub const BOUND_FRACTION: f64 = 0.8;
+
+/// `a[i] = b[i] + scalar * c[i]`, the STREAM triad kernel.
+fn triad(a: &mut [f64], b: &[f64], c: &[f64], scalar: f64) {
+    for ((a, b), c) in a.iter_mut().zip(b).zip(c) {
+        *a = b + scalar * c;
+    }
+}
+
+/// Measure the peak memory bandwidth in bytes per second with the triad.
+///
+/// Counts the two arrays read and the one written, like STREAM, which leaves
+/// out the write-allocate reads of `a`.
+pub fn measure_peak() -> f64 {
+    let mut a = vec![0f64; TRIAD_LEN];
+    let b = vec![1f64; TRIAD_LEN];
+    let c = vec![2f64; TRIAD_LEN];
+
+    // The first pass faults in the pages of `a`
+    triad(&mut a, &b, &c, 3.0);
+
+    let mut best = Duration::MAX;
+    for _ in 0..TRIAD_REPEATS {
+        let start = Instant::now();
+        triad(black_box(&mut a), black_box(&b), black_box(&c), 3.0);
+        best = best.min(start.elapsed());
+    }
+    black_box(&a);
+
+    let bytes = 3 * TRIAD_LEN * std::mem::size_of::<f64>();
+    bytes as f64 / best.as_secs_f64()
+}
+
+/// Achieved bandwidth in bytes per second when `bytes` are read in `mean_ns`.
+pub fn achieved(bytes: u64, mean_ns: f64) -> f64 {
+    bytes as f64 / (mean_ns * 1e-9)
+}
@@ -0,0 +1,203 @@
+//! Named snapshots of the measured results, for detecting regressions.
+//!
+//! A baseline is a copy of criterion's latest estimates, stored as
+//! tab-separated `full_id, mean_ns` rows in the `baselines` directory of
+//! criterion's output directory. Unlike criterion's own baselines, a later run
+//! is compared against it as a whole, and a regression of any benchmark beyond
+//! the threshold fails the comparison.
+
+use crate::results::{self, Estimate};
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    path::PathBuf,
+};
+
+/// Directory of the baselines in criterion's output directory.
+pub const DIR: &str = "baselines";
+/// Benchmarks slower than their baseline by more than this fraction regress.
+pub const DEFAULT_THRESHOLD: f64 = 0.05;
+
+/// Path of the baseline called `name`.
+pub fn path(name: &str) -> io::Result<PathBuf> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid baseline name: {}", name),
+        ));
+    }
+
+    Ok(results::criterion_dir()
+        .join(DIR)
+        .join(format!("{}.tsv", name)))
+}
+
+/// Save `estimates` as the baseline called `name`, replacing any earlier one.
+pub fn save(name: &str, estimates: &[Estimate]) -> io::Result<()> {
+    let path = path(name)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = io::BufWriter::new(fs::File::create(path)?);
+    for estimate in estimates {
+        writeln!(file, "{}\t{}", estimate.full_id, estimate.mean_ns)?;
+    }
+
+    file.flush()
+}
+
+/// Read the baseline called `name`.
+pub fn read(name: &str) -> io::Result<Vec<Estimate>> {
+    let file = fs::File::open(path(name)?)?;
+
+    let mut estimates = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        let mut fields = line.split('\t');
+        if let (Some(full_id), Some(Ok(mean_ns))) = (fields.next(), fields.next().map(str::parse)) {
+            estimates.push(Estimate {
+                full_id: full_id.to_string(),
+                mean_ns,
+                std_dev_ns: None,
+                throughput_bytes: None,
+                throughput_elements: None,
+            });
+        }
+    }
+
+    Ok(estimates)
+}
+
+/// A benchmark measured both in the baseline and in the current results.
+#[derive(Clone, Debug)]
+pub struct Change {
+    pub full_id: String,
+    pub baseline_ns: f64,
+    pub mean_ns: f64,
+}
+
+impl Change {
+    /// Change of the mean time relative to the baseline, positive when slower.
+    pub fn relative(&self) -> f64 {
+        self.mean_ns / self.baseline_ns - 1.0
+    }
+
+    pub fn regressed(&self, threshold: f64) -> bool {
+        self.relative() > threshold
+    }
+}
+
+/// The benchmarks of a baseline paired up with those of the current results.
+#[derive(Clone, Debug, Default)]
+pub struct Comparison {
+    pub changes: Vec<Change>,
+    /// Benchmarks of the baseline that weren't measured, which the comparison
+    /// can't vouch for.
+    pub missing: Vec<String>,
+    /// Benchmarks measured since the baseline was saved.
+    pub added: Vec<String>,
+}
+
+impl Comparison {
+    /// Whether every benchmark of the baseline was measured again, and at
+    /// least one was.
+    pub fn complete(&self) -> bool {
+        self.missing.is_empty() && !self.changes.is_empty()
+    }
+}
+
+/// Pair up the benchmarks of `current` with those of `baseline`, keeping track
+/// of those missing from either.
+pub fn compare(baseline: &[Estimate], current: &[Estimate]) -> Comparison {
+    let mut comparison = Comparison::default();
+    for estimate in current {
+        match baseline
+            .iter()
+            .find(|base| base.full_id == estimate.full_id)
+        {
+            Some(base) => comparison.changes.push(Change {
+                full_id: estimate.full_id.clone(),
+                baseline_ns: base.mean_ns,
+                mean_ns: estimate.mean_ns,
+            }),
+            None => comparison.added.push(estimate.full_id.clone()),
+        }
+    }
+    comparison.missing = baseline
+        .iter()
+        .filter(|base| {
+            !current
+                .iter()
+                .any(|estimate| estimate.full_id == base.full_id)
+        })
+        .map(|base| base.full_id.clone())
+        .collect();
+
+    comparison
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn estimate(full_id: &str, mean_ns: f64) -> Estimate {
+        Estimate {
+            full_id: full_id.to_string(),
+            mean_ns,
+            std_dev_ns: None,
+            throughput_bytes: None,
+            throughput_elements: None,
+        }
+    }
+
+    #[test]
+    fn flags_regressions_beyond_the_threshold() {
+        let baseline = [
+            estimate("a", 100.0),
+            estimate("b", 100.0),
+            estimate("c", 100.0),
+        ];
+        let current = [
+            estimate("a", 104.0),
+            estimate("b", 110.0),
+            estimate("c", 80.0),
+        ];
+        let comparison = compare(&baseline, &current);
+
+        assert!(comparison.complete());
+        let regressed: Vec<_> = comparison
+            .changes
+            .iter()
+            .filter(|change| change.regressed(DEFAULT_THRESHOLD))
+            .map(|change| change.full_id.as_str())
+            .collect();
+        assert_eq!(regressed, ["b"]);
+        assert!((comparison.changes[1].relative() - 0.1).abs() < 1e-12);
+    }
+
+    #[test]
+    fn is_incomplete_with_a_benchmark_missing_from_the_current_run() {
+        let baseline = [estimate("a", 100.0), estimate("b", 100.0)];
+        let current = [estimate("a", 100.0), estimate("c", 100.0)];
+        let comparison = compare(&baseline, &current);
+
+        assert!(!comparison.complete());
+        assert_eq!(comparison.missing, ["b"]);
+        assert_eq!(comparison.added, ["c"]);
+        assert_eq!(comparison.changes.len(), 1);
+        assert!(!comparison.changes[0].regressed(DEFAULT_THRESHOLD));
+    }
+
+    #[test]
+    fn is_incomplete_without_matching_benchmarks() {
+        let comparison = compare(&[estimate("a", 100.0)], &[estimate("b", 100.0)]);
+        assert!(!comparison.complete());
+        assert!(comparison.changes.is_empty());
+
+        // An empty baseline vouches for nothing either
+        let comparison = compare(&[], &[estimate("b", 100.0)]);
+        assert!(comparison.missing.is_empty());
+        assert!(!comparison.complete());
+    }
+}
@@ -0,0 +1,223 @@
+//! Expected results of the kernels, for checking the results of the measured
+//! ones.
+//!
+//! The expected sums are computed from the values the input was built from,
+//! before they went into a container, so that a container that lost or added
+//! values fails the check as well as a wrong kernel does.
+
+use crate::{accuracy, Element, Int, IntPrimitive, Primitive};
+use std::fmt;
+
+/// Sum of the squares of `values` in double precision, with Neumaier's
+/// compensated summation so that the expected value itself has no notable
+/// rounding error.
+pub fn expected_sum<'a, V, P>(values: impl IntoIterator<Item = &'a V>) -> f64
+where
+    V: Element<P> + 'a,
+    P: Primitive,
+{
+    let mut sum = 0.0f64;
+    let mut compensation = 0.0f64;
+    for &value in values {
+        let square = value.inner().to_f64().unwrap_or(f64::NAN).powi(2);
+        let t = sum + square;
+        if sum.abs() >= square.abs() {
+            compensation += (sum - t) + square;
+        } else {
+            compensation += (square - t) + sum;
+        }
+        sum = t;
+    }
+
+    sum + compensation
+}
+
+/// Relative error a kernel summing `len` squares in `P` may make, the error
+/// bound of [`accuracy::error_bound`] for the unit roundoff of `P`.
+pub fn tolerance<P: Primitive>(len: usize) -> f64 {
+    let epsilon = P::epsilon().to_f64().unwrap_or(f64::EPSILON);
+    accuracy::error_bound(len, epsilon / 2.0)
+}
+
+/// Whether `actual` is within the relative `tolerance` of `expected`. A
+/// non-finite expected sum only matches a non-finite one, as the kernels
+/// propagating NaN and infinity differ in which of them they return.
+pub fn sums_match(actual: f64, expected: f64, tolerance: f64) -> bool {
+    if !expected.is_finite() {
+        return !actual.is_finite();
+    }
+
+    actual == expected || (actual - expected).abs() <= tolerance * expected.abs()
+}
+
+/// Wrapping sum of the squares of `values`, which doesn't depend on the order
+/// of the values.
+pub fn expected_int_sum<'a, V, P>(values: impl IntoIterator<Item = &'a V>) -> P
+where
+    V: Int<P> + 'a,
+    P: IntPrimitive,
+{
+    let mut sum = P::zero();
+    for &value in values {
+        let x = value.inner();
+        sum = sum.wrapping_add(&x.wrapping_mul(&x));
+    }
+
+    sum
+}
+
+/// Sum a case is expected to return.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Expected {
+    /// A floating-point sum, within a relative tolerance.
+    Float { sum: f64, tolerance: f64 },
+    /// An integer sum, exactly.
+    Int(i128),
+}
+
+impl Expected {
+    /// The expected sum of the squares of `values`, summed in `P`.
+    pub fn of<'a, V, P>(values: impl IntoIterator<Item = &'a V>) -> Self
+    where
+        V: Element<P> + 'a,
+        P: Primitive,
+    {
+        let mut len = 0;
+        let sum = expected_sum(values.into_iter().inspect(|_| len += 1));
+        Expected::Float {
+            sum,
+            tolerance: tolerance::<P>(len),
+        }
+    }
+
+    /// The expected wrapping sum of the squares of `values`.
+    pub fn of_ints<'a, V, P>(values: impl IntoIterator<Item = &'a V>) -> Self
+    where
+        V: Int<P> + 'a,
+        P: IntPrimitive,
+    {
+        Expected::Int(expected_int_sum(values).to_i128().unwrap_or(0))
+    }
+
+    /// The expected sum of `repeats` sums of the same values, added up in
+    /// `f64`. Integer sums are assumed not to overflow.
+    pub fn repeated(self, repeats: usize) -> Self {
+        match self {
+            Expected::Float { sum, tolerance } => Expected::Float {
+                sum: sum * repeats as f64,
+                tolerance: tolerance + accuracy::error_bound(repeats, f64::EPSILON / 2.0),
+            },
+            Expected::Int(sum) => Expected::Int(sum * repeats as i128),
+        }
+    }
+
+    /// Whether a kernel returning `sum` computed the expected one.
+    pub fn matches(&self, sum: Sum) -> bool {
+        match (*self, sum) {
+            (Expected::Float { sum, tolerance }, Sum::Float(actual)) => {
+                sums_match(actual, sum, tolerance)
+            }
+            (Expected::Int(sum), Sum::Int(actual)) => actual == sum,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expected::Float { sum, tolerance } => write!(f, "{} ± {:e}", sum, tolerance),
+            Expected::Int(sum) => write!(f, "{}", sum),
+        }
+    }
+}
+
+/// Sum a kernel returned.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Sum {
+    Float(f64),
+    Int(i128),
+}
+
+impl fmt::Display for Sum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Sum::Float(sum) => write!(f, "{}", sum),
+            Sum::Int(sum) => write!(f, "{}", sum),
+        }
+    }
+}
+
+/// The result of a measured routine, checked against the [`Expected`] sum of
+/// its case.
+pub trait Checked {
+    /// The sum the routine returned, or `None` if it doesn't return one, as
+    /// the routines measuring clones, construction or drops don't.
+    fn sum(&self) -> Option<Sum>;
+}
+
+macro_rules! impl_checked {
+    ($variant:ident as $as:ty: $($ty:ty),+) => {
+        $(
+            impl Checked for $ty {
+                fn sum(&self) -> Option<Sum> {
+                    Some(Sum::$variant(*self as $as))
+                }
+            }
+        )+
+    };
+}
+
+impl_checked!(Float as f64: f32, f64);
+impl_checked!(Int as i128: i8, i16, i32, i64, i128, u8, u16, u32, u64, isize, usize);
+
+#[cfg(feature = "fixed")]
+impl Checked for crate::fixed_point::Q32 {
+    fn sum(&self) -> Option<Sum> {
+        Some(Sum::Float(self.to_num::<f64>()))
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl Checked for rust_decimal::Decimal {
+    fn sum(&self) -> Option<Sum> {
+        use rust_decimal::prelude::ToPrimitive;
+
+        Some(Sum::Float(self.to_f64().unwrap_or(f64::NAN)))
+    }
+}
+
+impl Checked for () {
+    fn sum(&self) -> Option<Sum> {
+        None
+    }
+}
+
+/// A sum returned along with something to drop outside the timed region.
+impl<T: Checked, U> Checked for (T, U) {
+    fn sum(&self) -> Option<Sum> {
+        self.0.sum()
+    }
+}
+
+/// The result of a routine that doesn't sum, such as the container a clone or
+/// a construction makes, returned so that it is dropped outside the timed
+/// region.
+pub struct NoSum<T>(pub T);
+
+impl<T> Checked for NoSum<T> {
+    fn sum(&self) -> Option<Sum> {
+        None
+    }
+}
+
+/// A routine failing on its input, as on non-finite values, is checked as a
+/// NaN sum.
+impl<T: Checked, E> Checked for Result<T, E> {
+    fn sum(&self) -> Option<Sum> {
+        match self {
+            Ok(sum) => sum.sum(),
+            Err(_) => Some(Sum::Float(f64::NAN)),
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Frequency scaling settings of the CPUs, read from sysfs on Linux.
+//!
+//! Timings are only comparable between runs when the clock is fixed: the
+//! `performance` governor keeps the cores at their highest regular frequency,
+//! and turbo boost, which depends on the temperature and the number of busy
+//! cores, is turned off.
+
+use crate::topology::{self, CPU_DIR};
+use std::{fmt, fs, io, path::Path};
+
+/// Governor that doesn't scale the frequency down when idle.
+pub const BENCHMARK_GOVERNOR: &str = "performance";
+
+/// Frequency scaling state of the machine.
+#[derive(Clone, Debug, Default)]
+pub struct State {
+    /// Distinct scaling governors of the CPUs, sorted.
+    pub governors: Vec<String>,
+    /// Whether turbo boost is enabled, if the driver reports it.
+    pub turbo: Option<bool>,
+}
+
+impl State {
+    pub fn read() -> io::Result<Self> {
+        let mut governors = Vec::new();
+        for path in topology::cpu_dirs()? {
+            // CPUs without frequency scaling, or offline ones, have no governor
+            match fs::read_to_string(path.join("cpufreq/scaling_governor")) {
+                Ok(governor) => governors.push(governor.trim().to_string()),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        governors.sort_unstable();
+        governors.dedup();
+
+        Ok(State {
+            governors,
+            turbo: turbo()?,
+        })
+    }
+
+    /// The settings that make the measurements noisy.
+    pub fn issues(&self) -> Vec<Issue> {
+        let mut issues = self
+            .governors
+            .iter()
+            .filter(|governor| *governor != BENCHMARK_GOVERNOR)
+            .map(|governor| Issue::Governor(governor.clone()))
+            .collect::<Vec<_>>();
+        if self.turbo == Some(true) {
+            issues.push(Issue::Turbo);
+        }
+
+        issues
+    }
+}
+
+/// A setting that lets the CPU frequency change during the run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Issue {
+    /// Some CPUs use this governor instead of `performance`.
+    Governor(String),
+    /// Turbo boost is enabled.
+    Turbo,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::Governor(governor) => write!(
+                f,
+                "scaling governor is {}, not {}",
+                governor, BENCHMARK_GOVERNOR
+            ),
+            Issue::Turbo => write!(f, "turbo boost is enabled"),
+        }
+    }
+}
+
+/// Whether turbo boost is enabled, from the `intel_pstate` driver or the
+/// generic `cpufreq` boost switch, whichever exists.
+fn turbo() -> io::Result<Option<bool>> {
+    let intel_pstate = Path::new(CPU_DIR).join("intel_pstate/no_turbo");
+    let boost = Path::new(CPU_DIR).join("cpufreq/boost");
+
+    if let Some(no_turbo) = read_flag(&intel_pstate)? {
+        return Ok(Some(!no_turbo));
+    }
+    read_flag(&boost)
+}
+
+/// Read a `0` or `1` flag, or `None` if the file doesn't exist.
+fn read_flag(path: &Path) -> io::Result<Option<bool>> {
+    match fs::read_to_string(path) {
+        Ok(flag) => match flag.trim() {
+            "0" => Ok(Some(false)),
+            "1" => Ok(Some(true)),
+            flag => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid flag in {}: {}", path.display(), flag),
+            )),
+        },
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,531 @@
+use crate::{Element, Int, IntPrimitive, Primitive};
+use float_ord::FloatOrd;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use std::{
+    collections::HashSet,
+    fmt, iter,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// Seed of the generated inputs unless configured otherwise.
+pub const DEFAULT_SEED: u64 = 0x5eed;
+
+static SEED: AtomicU64 = AtomicU64::new(DEFAULT_SEED);
+
+/// Seed the generators of all inputs created from now on.
+pub fn set_seed(seed: u64) {
+    SEED.store(seed, Ordering::Relaxed);
+}
+
+pub fn seed() -> u64 {
+    SEED.load(Ordering::Relaxed)
+}
+
+/// A generator for a new input.
+///
+/// Every input starts from the same seed, so an input depends only on its
+/// length and element type, and not on what was generated before it: the
+/// containers of a size all hold the same values, in every run and on every
+/// machine built from the same lock file.
+pub fn rng() -> StdRng {
+    StdRng::seed_from_u64(seed())
+}
+
+/// Shape of the randomly generated input values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Distribution {
+    /// Uniform over [0, 1), the distribution of the inputs unless stated
+    /// otherwise.
+    Uniform,
+    /// Uniform exponent over [2^-64, 2^64), spanning a wide dynamic range so
+    /// that rounding error in the accumulator becomes visible.
+    LogUniform,
+    /// Standard normal, with both signs and most values near zero.
+    Normal,
+    /// `e^x` of a standard normal `x`, positive and skewed towards large values.
+    LogNormal,
+    /// Ranks 1 to `ZIPF_RANKS` with a probability falling as a power of the
+    /// rank, so that a few values make up most of the input, as in real-world
+    /// frequency data.
+    Zipf,
+}
+
+/// Number of distinct values of the Zipf distribution.
+pub const ZIPF_RANKS: f64 = 1000.0;
+/// Exponent of the Zipf distribution: rank `k` has a probability proportional
+/// to `k^-ZIPF_EXPONENT`.
+pub const ZIPF_EXPONENT: f64 = 1.1;
+
+impl Distribution {
+    pub const ALL: [Distribution; 5] = [
+        Distribution::Uniform,
+        Distribution::LogUniform,
+        Distribution::Normal,
+        Distribution::LogNormal,
+        Distribution::Zipf,
+    ];
+
+    /// Draw a single value from the distribution.
+    pub fn sample<R: Rng + ?Sized>(self, rng: &mut R) -> f64 {
+        match self {
+            Distribution::Uniform => rng.gen(),
+            Distribution::LogUniform => 2f64.powf(rng.gen_range(-64.0..64.0)),
+            Distribution::Normal => standard_normal(rng),
+            Distribution::LogNormal => standard_normal(rng).exp(),
+            Distribution::Zipf => {
+                // Inverse of the CDF of a power law over [1, ZIPF_RANKS + 1),
+                // rounded down to the rank
+                let a = 1.0 - ZIPF_EXPONENT;
+                let u: f64 = rng.gen();
+                let x = (((ZIPF_RANKS + 1.0).powf(a) - 1.0) * u + 1.0).powf(1.0 / a);
+                x.floor().min(ZIPF_RANKS)
+            }
+        }
+    }
+}
+
+/// Draw from the standard normal distribution with the Box-Muller transform.
+fn standard_normal<R: Rng + ?Sized>(rng: &mut R) -> f64 {
+    // 1 - [0, 1) is never zero, so the logarithm is finite
+    let u1 = 1.0 - rng.gen::<f64>();
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+impl fmt::Display for Distribution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Distribution::Uniform => write!(f, "uniform"),
+            Distribution::LogUniform => write!(f, "log-uniform"),
+            Distribution::Normal => write!(f, "normal"),
+            Distribution::LogNormal => write!(f, "log-normal"),
+            Distribution::Zipf => write!(f, "zipf"),
+        }
+    }
+}
+
+/// Order of the generated values, before they are inserted into the container.
+///
+/// Besides the accumulation order of the sum, the insertion order decides the
+/// internal layout of the hashed and tree containers and how well the branches
+/// of a comparing kernel are predicted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Order {
+    /// As drawn from the random generator.
+    Scrambled,
+    /// Sorted by ascending magnitude, so that small squares are accumulated
+    /// before the running sum grows large.
+    AscendingMagnitude,
+    /// Sorted by descending magnitude, so that small squares are added to an
+    /// already large running sum.
+    DescendingMagnitude,
+    /// Sorted by ascending magnitude, then with `PARTIAL_SWAP_FRACTION` of the
+    /// values swapped with others at random.
+    PartiallySorted,
+}
+
+/// Fraction of the values moved out of place in a partially sorted input.
+pub const PARTIAL_SWAP_FRACTION: f64 = 0.1;
+/// Mixed into the seed of the swaps of a partially sorted input, so that which
+/// values move does not follow from the stream that generated them.
+const PARTIAL_SWAP_STREAM: u64 = 0x9e37_79b9_7f4a_7c15;
+
+impl Order {
+    pub const ALL: [Order; 4] = [
+        Order::Scrambled,
+        Order::AscendingMagnitude,
+        Order::DescendingMagnitude,
+        Order::PartiallySorted,
+    ];
+
+    /// Reorder `data` in place.
+    pub fn apply<V, P>(self, data: &mut [V])
+    where
+        V: Element<P>,
+        P: Primitive,
+    {
+        let by_magnitude = |a: &V, b: &V| {
+            a.inner()
+                .abs()
+                .partial_cmp(&b.inner().abs())
+                .expect("generated values are never NaN")
+        };
+
+        match self {
+            Order::Scrambled => {}
+            Order::AscendingMagnitude => data.sort_unstable_by(by_magnitude),
+            Order::DescendingMagnitude => data.sort_unstable_by(|a, b| by_magnitude(b, a)),
+            Order::PartiallySorted => {
+                data.sort_unstable_by(by_magnitude);
+                if data.is_empty() {
+                    return;
+                }
+                let mut rng = StdRng::seed_from_u64(seed() ^ PARTIAL_SWAP_STREAM);
+                let swaps = (data.len() as f64 * PARTIAL_SWAP_FRACTION / 2.0) as usize;
+                for _ in 0..swaps {
+                    let i = rng.gen_range(0..data.len());
+                    let j = rng.gen_range(0..data.len());
+                    data.swap(i, j);
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Order {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Order::Scrambled => write!(f, "scrambled"),
+            Order::AscendingMagnitude => write!(f, "sorted"),
+            Order::DescendingMagnitude => write!(f, "reverse sorted"),
+            Order::PartiallySorted => write!(f, "partially sorted"),
+        }
+    }
+}
+
+/// Create the concrete data-structure of length `n` using FromIterator<V> where
+/// V is the element type.
+pub fn create_scrambled_data<V, T, P>(n: usize) -> T
+where
+    V: Element<P>,
+    P: Primitive,
+    T: iter::FromIterator<V>,
+{
+    create_scrambled_data_with(n, Distribution::Uniform)
+}
+
+/// Like [`create_scrambled_data`], but drawing the values from `distribution`.
+///
+/// Values are drawn at f64 and rounded to the backing primitive `P`.
+pub fn create_scrambled_data_with<V, T, P>(n: usize, distribution: Distribution) -> T
+where
+    V: Element<P>,
+    P: Primitive,
+    T: iter::FromIterator<V>,
+{
+    let mut rng = rng();
+
+    (0..n)
+        .map(|_| {
+            let x = distribution.sample(&mut rng);
+            V::create(num_traits::cast(x).expect("sample is representable in P"))
+        })
+        .collect()
+}
+
+/// Like [`create_scrambled_data`], but with `n` distinct values, so that a set
+/// built from them holds as many elements as a sequence.
+pub fn create_distinct_data<V, T, P>(n: usize) -> T
+where
+    V: Element<P>,
+    P: Primitive,
+    T: iter::FromIterator<V>,
+{
+    create_distinct_data_with(n, Distribution::Uniform)
+}
+
+/// Like [`create_scrambled_data_with`], but with every value distinct after
+/// rounding to `P`.
+///
+/// The values are drawn as usual and sorted, then each one that isn't below
+/// its successor is lowered just below it, by an ulp or two, and the values
+/// are shuffled back into a random order. Nothing is drawn again, so the
+/// values keep the distribution as far as `P` can resolve it. Where it can't,
+/// as for the few ranks of the Zipf distribution or more than 2^24 uniform
+/// `f32` values, the repeats are spread downwards, where the representable
+/// values are denser.
+pub fn create_distinct_data_with<V, T, P>(n: usize, distribution: Distribution) -> T
+where
+    V: Element<P>,
+    P: Primitive,
+    T: iter::FromIterator<V>,
+{
+    let mut rng = rng();
+    let mut values: Vec<P> = (0..n)
+        .map(|_| {
+            num_traits::cast(distribution.sample(&mut rng)).expect("sample is representable in P")
+        })
+        .collect();
+
+    values.sort_unstable_by(|a, b| a.partial_cmp(b).expect("generated values are never NaN"));
+    for i in (0..values.len().saturating_sub(1)).rev() {
+        let next = values[i + 1];
+        if values[i] >= next {
+            values[i] = next - (next.abs() * P::epsilon()).max(P::min_positive_value());
+        }
+    }
+    values.shuffle(&mut rng);
+
+    values.into_iter().map(V::create).collect()
+}
+
+/// Number of low bits that the hashes of the colliding values share.
+pub const COLLIDING_HASH_BITS: u32 = 8;
+
+/// Create a data-structure of `n` distinct values whose FxHash hashes collide
+/// into 1 in 2^`COLLIDING_HASH_BITS` buckets of a hash table.
+///
+/// `FloatOrd` hashes the bits of the value, and FxHash hashes a single word by
+/// multiplying it with an odd constant, so values with their low bits zeroed
+/// have hashes with the same low bits zeroed. The table picks the bucket to
+/// start probing at from those low bits, so every value starts from one of a
+/// few buckets and probes past the values inserted before it. The values have
+/// random exponents in [2^0, 2^64) and random mantissas otherwise.
+///
+/// The keyed hashers, SipHash and aHash, can't be targeted like this.
+pub fn create_colliding_data<T>(n: usize) -> T
+where
+    T: iter::FromIterator<FloatOrd<f64>>,
+{
+    let mut rng = rng();
+    let mut seen = HashSet::with_capacity(n);
+    let mut values = Vec::with_capacity(n);
+    let mantissa_mask = ((1u64 << 52) - 1) & !((1u64 << COLLIDING_HASH_BITS) - 1);
+
+    while values.len() < n {
+        let exponent = rng.gen_range(1023..1023 + 64u64);
+        let bits = exponent << 52 | (rng.gen::<u64>() & mantissa_mask);
+        if seen.insert(bits) {
+            values.push(FloatOrd(f64::from_bits(bits)));
+        }
+    }
+
+    values.into_iter().collect()
+}
+
+/// Like [`create_distinct_data_with`], but reordering the values by `order`
+/// before collecting them into the container.
+pub fn create_ordered_data<V, T, P>(n: usize, distribution: Distribution, order: Order) -> T
+where
+    V: Element<P>,
+    P: Primitive,
+    T: iter::FromIterator<V>,
+{
+    let mut data: Vec<V> = create_distinct_data_with(n, distribution);
+    order.apply(&mut data);
+
+    data.into_iter().collect()
+}
+
+/// Like [`create_scrambled_data`], but with each value replaced with
+/// probability `fraction` by a subnormal value of `P`, one below the smallest
+/// normal value. Squaring a subnormal underflows, so the input and the result
+/// of its multiplication are both off the fast path of many CPUs.
+pub fn create_subnormal_data<V, T, P>(n: usize, fraction: f64) -> T
+where
+    V: Element<P>,
+    P: Primitive,
+    T: iter::FromIterator<V>,
+{
+    let mut rng = rng();
+
+    (0..n)
+        .map(|_| {
+            let x = if rng.gen_bool(fraction) {
+                // At least half of the smallest normal value, so never zero
+                let scale: P =
+                    num_traits::cast(rng.gen_range(0.5..1.0)).expect("scale is representable in P");
+                P::min_positive_value() * scale
+            } else {
+                num_traits::cast(rng.gen::<f64>()).expect("sample is representable in P")
+            };
+            V::create(x)
+        })
+        .collect()
+}
+
+/// Like [`create_scrambled_data`], but with each value replaced with
+/// probability `fraction` by NaN, positive or negative infinity, in equal
+/// shares.
+pub fn create_non_finite_data<V, T, P>(n: usize, fraction: f64) -> T
+where
+    V: Element<P>,
+    P: Primitive,
+    T: iter::FromIterator<V>,
+{
+    let mut rng = rng();
+
+    (0..n)
+        .map(|_| {
+            let x = if rng.gen_bool(fraction) {
+                match rng.gen_range(0..3) {
+                    0 => P::nan(),
+                    1 => P::infinity(),
+                    _ => P::neg_infinity(),
+                }
+            } else {
+                num_traits::cast(rng.gen::<f64>()).expect("sample is representable in P")
+            };
+            V::create(x)
+        })
+        .collect()
+}
+
+/// Like [`create_scrambled_data`], but keying each value by its index, for
+/// maps like `HashMap<usize, V>`.
+pub fn create_keyed_data<V, T, P>(n: usize) -> T
+where
+    V: Element<P>,
+    P: Primitive,
+    T: iter::FromIterator<(usize, V)>,
+{
+    let data: Vec<V> = create_scrambled_data(n);
+
+    data.into_iter().enumerate().collect()
+}
+
+/// Create a data-structure of `n` integers, uniformly random over the whole
+/// range of `P`.
+pub fn create_scrambled_ints<V, T, P>(n: usize) -> T
+where
+    V: Int<P>,
+    P: IntPrimitive,
+    T: iter::FromIterator<V>,
+    rand::distributions::Standard: rand::distributions::Distribution<P>,
+{
+    let mut rng = rng();
+
+    (0..n).map(|_| V::create(rng.gen::<P>())).collect()
+}
+
+/// Like [`create_scrambled_ints`], but with `n` distinct values, so that a set
+/// built from them holds as many elements as a sequence.
+///
+/// Like [`create_distinct_data_with`], the values are drawn as usual and
+/// sorted, each one that isn't below its successor is lowered to one below it,
+/// and the values are shuffled back into a random order. The repeats that
+/// reach the bottom of the range of `P` are then raised above their
+/// predecessors instead, so `n` can be as large as the number of values of `P`.
+pub fn create_distinct_ints<V, T, P>(n: usize) -> T
+where
+    V: Int<P>,
+    P: IntPrimitive,
+    T: iter::FromIterator<V>,
+    rand::distributions::Standard: rand::distributions::Distribution<P>,
+{
+    let mut rng = rng();
+    let mut values: Vec<P> = (0..n).map(|_| rng.gen::<P>()).collect();
+
+    values.sort_unstable();
+    for i in (0..values.len().saturating_sub(1)).rev() {
+        let next = values[i + 1];
+        if values[i] >= next {
+            values[i] = next.saturating_sub(P::one());
+        }
+    }
+    for i in 1..values.len() {
+        let previous = values[i - 1];
+        if values[i] <= previous {
+            values[i] = previous
+                .checked_add(&P::one())
+                .expect("n is at most the number of values of P");
+        }
+    }
+    values.shuffle(&mut rng);
+
+    values.into_iter().map(V::create).collect()
+}
+
+/// Create a data-structure of `n` complex values, with both parts uniform over
+/// [0, 1).
+pub fn create_scrambled_complex<T>(n: usize) -> T
+where
+    T: iter::FromIterator<num_complex::Complex<f64>>,
+{
+    let mut rng = rng();
+
+    (0..n)
+        .map(|_| num_complex::Complex::new(rng.gen(), rng.gen()))
+        .collect()
+}
+
+/// Create a data-structure of `n` uniformly random bytes, for the quantized
+/// element variant.
+pub fn create_scrambled_bytes<T>(n: usize) -> T
+where
+    T: iter::FromIterator<u8>,
+{
+    let mut rng = rng();
+
+    (0..n).map(|_| rng.gen::<u8>()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Inner;
+    use std::collections::BTreeSet;
+
+    /// A byte, as an integer element, to cover every value of a primitive.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    struct Byte(u8);
+
+    impl Inner for Byte {
+        type InnerType = u8;
+
+        fn inner(self) -> u8 {
+            self.0
+        }
+
+        fn create(inner: u8) -> Self {
+            Byte(inner)
+        }
+    }
+
+    impl Int<u8> for Byte {}
+
+    fn distinct_len<P>(values: &[FloatOrd<P>]) -> usize
+    where
+        FloatOrd<P>: Ord,
+    {
+        values.iter().collect::<BTreeSet<_>>().len()
+    }
+
+    #[test]
+    fn distinct_data_has_no_repeats() {
+        for &distribution in Distribution::ALL.iter() {
+            let values: Vec<FloatOrd<f64>> = create_distinct_data_with(10_000, distribution);
+            assert_eq!(values.len(), 10_000);
+            assert_eq!(distinct_len(&values), 10_000, "{}", distribution);
+        }
+    }
+
+    #[test]
+    fn distinct_data_spreads_the_repeats_of_the_zipf_ranks() {
+        // 1000 ranks can't hold 10 000 distinct values
+        let n = 10_000;
+        let scrambled: Vec<FloatOrd<f64>> = create_scrambled_data_with(n, Distribution::Zipf);
+        assert!(distinct_len(&scrambled) <= ZIPF_RANKS as usize);
+
+        let values: Vec<FloatOrd<f64>> = create_distinct_data_with(n, Distribution::Zipf);
+        assert_eq!(distinct_len(&values), n);
+        let values: Vec<FloatOrd<f32>> = create_distinct_data_with(n, Distribution::Zipf);
+        assert_eq!(distinct_len(&values), n);
+    }
+
+    #[test]
+    fn distinct_data_spreads_the_repeats_of_many_f32s() {
+        // Uniform f32s near 1 are 2^-24 apart, so a few thousand of them
+        // already repeat
+        let n = 1 << 18;
+        let scrambled: Vec<FloatOrd<f32>> = create_scrambled_data(n);
+        assert!(distinct_len(&scrambled) < n);
+
+        let values: Vec<FloatOrd<f32>> = create_distinct_data(n);
+        assert_eq!(distinct_len(&values), n);
+    }
+
+    #[test]
+    fn distinct_ints_have_no_repeats() {
+        let values: Vec<i32> = create_distinct_ints(100_000);
+        assert_eq!(values.iter().collect::<HashSet<_>>().len(), 100_000);
+        let values: Vec<i64> = create_distinct_ints(100_000);
+        assert_eq!(values.iter().collect::<HashSet<_>>().len(), 100_000);
+    }
+
+    #[test]
+    fn distinct_ints_can_take_every_value_of_the_primitive() {
+        let values: Vec<Byte> = create_distinct_ints(256);
+        assert_eq!(values.iter().collect::<HashSet<_>>().len(), 256);
+    }
+}
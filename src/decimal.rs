@@ -0,0 +1,47 @@
+//! Decimal floating point elements, for comparing against binary floating point
+//! on the same containers.
+//!
+//! A `Decimal` is a 96-bit integer with a decimal scale, 16 bytes per element,
+//! and its arithmetic is done in software.
+
+use crate::data;
+use rand::seq::index;
+use rust_decimal::Decimal;
+use std::iter;
+
+/// Least number of decimal places of the generated values.
+const SCALE: u32 = 6;
+
+/// Create a data-structure of `n` distinct decimal values, uniform over [0, 1)
+/// in steps of 10^-6, so that squares and sums are exact.
+///
+/// The values are drawn without replacement, so that a set built from them
+/// holds as many elements as a sequence. Inputs of more than 10^6 values get
+/// as many more decimal places as they need to stay distinct.
+pub fn create_scrambled_decimals<T>(n: usize) -> T
+where
+    T: iter::FromIterator<Decimal>,
+{
+    let mut rng = data::rng();
+    let mut scale = SCALE;
+    while 10usize.pow(scale) < n {
+        scale += 1;
+    }
+
+    // The sampled indices come out in a random order
+    index::sample(&mut rng, 10usize.pow(scale), n)
+        .into_iter()
+        .map(|mantissa| Decimal::new(mantissa as i64, scale))
+        .collect()
+}
+
+/// Sum the square of each value, referencing the data-structure immutably.
+pub fn sum_of_squares_decimal_by_ref<T>(collection: &T) -> Decimal
+where
+    T: ?Sized,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a Decimal>,
+{
+    collection
+        .into_iter()
+        .fold(Decimal::ZERO, |sum, &x| sum + x * x)
+}
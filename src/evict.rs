@@ -0,0 +1,57 @@
+//! Evicting the inputs from the caches before a cold measurement.
+//!
+//! Flushing an input with `clflush` would need to know where all of its nodes
+//! are, so the caches are flushed by reading a dummy buffer several times the
+//! size of the last-level cache instead. Replacement policies are not strict
+//! LRU, so a few lines of the input may survive.
+
+use crate::aligned::CACHE_LINE;
+use criterion::black_box;
+
+/// The dummy buffer spans this many times the last-level cache.
+const LLC_MULTIPLE: usize = 4;
+/// Size of the dummy buffer when the caches aren't known, 4x the 16 MB
+/// last-level cache of the test platform.
+pub const DEFAULT_BUFFER_BYTES: usize = 64 * 1024 * 1024;
+
+/// Size of the dummy buffer for the caches of `capacities`, 4x the largest of
+/// them, rounded down to whole cache lines.
+pub fn buffer_bytes(capacities: &[usize]) -> usize {
+    match capacities.iter().copied().max() {
+        Some(llc) if llc > 0 => LLC_MULTIPLE * llc / CACHE_LINE * CACHE_LINE,
+        _ => DEFAULT_BUFFER_BYTES,
+    }
+}
+
+pub struct Evictor {
+    buffer: Box<[u64]>,
+}
+
+impl Evictor {
+    /// Allocate a dummy buffer of `buffer_bytes`, see [`buffer_bytes`], and
+    /// fault in its pages.
+    pub fn new(buffer_bytes: usize) -> Self {
+        let buffer = vec![1u64; buffer_bytes / std::mem::size_of::<u64>()].into_boxed_slice();
+        Evictor { buffer }
+    }
+
+    /// Read a word from every cache line of the dummy buffer.
+    ///
+    /// The buffer is only read, so that the evicting lines are clean and
+    /// writing them back doesn't slow down the measurement that follows.
+    pub fn evict(&self) {
+        let words_per_line = CACHE_LINE / std::mem::size_of::<u64>();
+        let sum = self
+            .buffer
+            .iter()
+            .step_by(words_per_line)
+            .fold(0u64, |sum, &word| sum.wrapping_add(word));
+        black_box(sum);
+    }
+}
+
+impl Default for Evictor {
+    fn default() -> Self {
+        Self::new(DEFAULT_BUFFER_BYTES)
+    }
+}
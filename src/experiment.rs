@@ -0,0 +1,498 @@
+//! Experiment configuration read from a TOML file.
+//!
+//! The file in `SOS_EXPERIMENT`, or `experiment.toml` in the working directory
+//! if there is one, declares the benchmark matrix to run. Every setting is
+//! optional and falls back to the defaults of the bench binary:
+//!
+//! ```toml
+//! [sizes]
+//! # Input sizes as powers of two: 2^10 = 1 kB up to 2^26 = 64 MB
+//! start_pow = 10
+//! end_pow = 26
+//! step_pow = 2
+//!
+//! [matrix]
+//! # Benchmark groups to run, by name
+//! groups = ["Sum of squares", "Sum of squares (f32)"]
+//! # Cases to run by container and kernel, see `Experiment::selects`
+//! containers = ["vec", "btreeset"]
+//! kernels = ["by reference"]
+//!
+//! [criterion]
+//! sample_size = 100
+//! # In seconds
+//! measurement_time = 5.0
+//! warm_up_time = 3.0
+//! # Report throughput in "bytes" or "elements"
+//! throughput = "bytes"
+//!
+//! [data]
+//! # Seed of the generated inputs
+//! seed = 24301
+//! ```
+//!
+//! The containers and kernels can also be given as comma-separated lists in
+//! `SOS_CONTAINERS` and `SOS_KERNELS`, the throughput unit in `SOS_THROUGHPUT`
+//! and the seed in `SOS_SEED`, which take precedence over the file.
+
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Configuration file read when `SOS_EXPERIMENT` isn't set.
+pub const DEFAULT_PATH: &str = "experiment.toml";
+
+#[derive(Clone, Debug, Default)]
+pub struct Experiment {
+    /// The file the experiment was read from, if any.
+    pub path: Option<PathBuf>,
+    pub start_pow: Option<u32>,
+    pub end_pow: Option<u32>,
+    pub step_pow: Option<u32>,
+    /// Names of the benchmark groups to run, or all of them if not given.
+    pub groups: Option<Vec<String>>,
+    /// Containers to run, or all of them if not given.
+    pub containers: Option<Vec<String>>,
+    /// Kernels to run, or all of them if not given.
+    pub kernels: Option<Vec<String>>,
+    pub sample_size: Option<usize>,
+    pub measurement_time: Option<Duration>,
+    pub warm_up_time: Option<Duration>,
+    pub throughput: ThroughputUnit,
+    /// Seed of the generated inputs, or `data::DEFAULT_SEED` if not given.
+    pub seed: Option<u64>,
+}
+
+/// Unit of the throughput criterion reports.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ThroughputUnit {
+    /// Bytes of input per second, comparable across element types only as
+    /// memory traffic.
+    #[default]
+    Bytes,
+    /// Elements per second, comparable across element types of different sizes.
+    Elements,
+}
+
+impl std::str::FromStr for ThroughputUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bytes" => Ok(ThroughputUnit::Bytes),
+            "elements" => Ok(ThroughputUnit::Elements),
+            _ => Err(format!("unknown throughput unit: {}", s)),
+        }
+    }
+}
+
+impl Experiment {
+    /// Read the experiment from `SOS_EXPERIMENT` or the default path. Without
+    /// either, the experiment is empty.
+    pub fn load() -> io::Result<Self> {
+        let path = match env::var_os("SOS_EXPERIMENT") {
+            Some(path) => Some(PathBuf::from(path)),
+            None if Path::new(DEFAULT_PATH).is_file() => Some(PathBuf::from(DEFAULT_PATH)),
+            None => None,
+        };
+
+        let mut experiment = match &path {
+            Some(path) => Self::parse(&fs::read_to_string(path)?)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+            None => Experiment::default(),
+        };
+        experiment.path = path;
+        if let Ok(containers) = env::var("SOS_CONTAINERS") {
+            experiment.containers = Some(split_list(&containers));
+        }
+        if let Ok(kernels) = env::var("SOS_KERNELS") {
+            experiment.kernels = Some(split_list(&kernels));
+        }
+        if let Ok(throughput) = env::var("SOS_THROUGHPUT") {
+            experiment.throughput = throughput
+                .parse()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        }
+        if let Ok(seed) = env::var("SOS_SEED") {
+            let seed = seed.parse().map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("invalid SOS_SEED: {}: {}", seed, e),
+                )
+            })?;
+            experiment.seed = Some(seed);
+        }
+
+        Ok(experiment)
+    }
+
+    /// Parse an experiment from the contents of its TOML file.
+    pub fn parse(toml: &str) -> Result<Self, String> {
+        let value = toml.parse::<toml::Value>().map_err(|e| e.to_string())?;
+        let sizes = Section::new(&value, "sizes")?;
+        let matrix = Section::new(&value, "matrix")?;
+        let criterion = Section::new(&value, "criterion")?;
+        let data = Section::new(&value, "data")?;
+
+        let sample_size = criterion.integer("sample_size")?;
+        // Criterion panics on fewer samples
+        if matches!(sample_size, Some(n) if n < 10) {
+            return Err("criterion.sample_size must be at least 10".to_string());
+        }
+
+        Ok(Experiment {
+            path: None,
+            start_pow: sizes.integer("start_pow")?,
+            end_pow: sizes.integer("end_pow")?,
+            step_pow: sizes.integer("step_pow")?,
+            groups: matrix.strings("groups")?,
+            containers: matrix.strings("containers")?,
+            kernels: matrix.strings("kernels")?,
+            sample_size,
+            measurement_time: criterion.seconds("measurement_time")?,
+            warm_up_time: criterion.seconds("warm_up_time")?,
+            throughput: match criterion.get("throughput") {
+                Some(value) => value
+                    .as_str()
+                    .ok_or_else(|| criterion.invalid("throughput", "a string"))?
+                    .parse()?,
+                None => ThroughputUnit::default(),
+            },
+            seed: data.integer("seed")?,
+        })
+    }
+
+    /// A criterion filter matching the benchmarks of the selected groups.
+    pub fn group_filter(&self) -> Option<String> {
+        let groups = self.groups.as_ref()?;
+        let alternatives = groups
+            .iter()
+            .map(|group| escape_regex(group))
+            .collect::<Vec<_>>();
+
+        Some(format!("^(?:{})/", alternatives.join("|")))
+    }
+
+    /// Whether the benchmark group `name` is selected by the groups of the
+    /// experiment, as by [`Experiment::group_filter`].
+    pub fn selects_group(&self, name: &str) -> bool {
+        match &self.groups {
+            Some(groups) => groups.iter().any(|group| group == name),
+            None => true,
+        }
+    }
+
+    /// Whether the case named `ds_name` is selected by the containers and
+    /// kernels of the experiment.
+    ///
+    /// Cases are named `container (kernel)`, as in `Vec (by reference)`, or
+    /// just `container` for the plain kernel. Names are compared ignoring case
+    /// and anything but letters and digits, and a container also matches
+    /// without its type parameters: `smallvec` selects both `SmallVec<16>` and
+    /// `SmallVec<1024>`. Cases without a kernel are left out when kernels are
+    /// selected.
+    pub fn selects(&self, ds_name: &str) -> bool {
+        // Tags like `[cold]` or `[alloc 2]` mark variants of the same case
+        let ds_name = ds_name.split(" [").next().unwrap_or(ds_name);
+        let (container, kernel) = match ds_name.find(" (") {
+            Some(i) => (&ds_name[..i], Some(ds_name[i + 2..].trim_end_matches(')'))),
+            None => (ds_name, None),
+        };
+
+        let container_selected = match &self.containers {
+            Some(containers) => {
+                let base = container.split('<').next().unwrap_or(container);
+                containers.iter().any(|selected| {
+                    let selected = normalize(selected);
+                    selected == normalize(container) || selected == normalize(base)
+                })
+            }
+            None => true,
+        };
+        let kernel_selected = match (&self.kernels, kernel) {
+            (Some(kernels), Some(kernel)) => kernels
+                .iter()
+                .any(|selected| normalize(selected) == normalize(kernel)),
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+
+        container_selected && kernel_selected
+    }
+}
+
+/// Split a comma-separated list, skipping empty items.
+fn split_list(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Lowercase `name` and drop everything but letters and digits.
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// A table of the experiment file, possibly missing.
+struct Section<'a> {
+    name: &'a str,
+    table: Option<&'a toml::value::Table>,
+}
+
+impl<'a> Section<'a> {
+    fn new(value: &'a toml::Value, name: &'a str) -> Result<Self, String> {
+        let table = match value.get(name) {
+            Some(section) => Some(
+                section
+                    .as_table()
+                    .ok_or_else(|| format!("{} must be a table", name))?,
+            ),
+            None => None,
+        };
+
+        Ok(Section { name, table })
+    }
+
+    fn get(&self, key: &str) -> Option<&'a toml::Value> {
+        self.table.and_then(|table| table.get(key))
+    }
+
+    fn invalid(&self, key: &str, expected: &str) -> String {
+        format!("{}.{} must be {}", self.name, key, expected)
+    }
+
+    fn integer<T: std::convert::TryFrom<i64>>(&self, key: &str) -> Result<Option<T>, String> {
+        self.get(key)
+            .map(|value| {
+                value
+                    .as_integer()
+                    .and_then(|n| T::try_from(n).ok())
+                    .ok_or_else(|| self.invalid(key, "a non-negative integer"))
+            })
+            .transpose()
+    }
+
+    fn seconds(&self, key: &str) -> Result<Option<Duration>, String> {
+        self.get(key)
+            .map(|value| {
+                value
+                    .as_float()
+                    .or_else(|| value.as_integer().map(|n| n as f64))
+                    .filter(|&secs| secs > 0.0 && secs.is_finite())
+                    .map(Duration::from_secs_f64)
+                    .ok_or_else(|| self.invalid(key, "a positive number of seconds"))
+            })
+            .transpose()
+    }
+
+    fn strings(&self, key: &str) -> Result<Option<Vec<String>>, String> {
+        self.get(key)
+            .map(|value| {
+                value
+                    .as_array()
+                    .and_then(|values| {
+                        values
+                            .iter()
+                            .map(|value| value.as_str().map(String::from))
+                            .collect()
+                    })
+                    .ok_or_else(|| self.invalid(key, "an array of strings"))
+            })
+            .transpose()
+    }
+}
+
+/// Escape the characters of `literal` that are special in a regex.
+fn escape_regex(literal: &str) -> String {
+    let mut escaped = String::with_capacity(literal.len());
+    for c in literal.chars() {
+        if "\\.+*?()|[]{}^$#&-~".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(toml: &str) -> Experiment {
+        Experiment::parse(toml).unwrap()
+    }
+
+    fn selecting(containers: &[&str], kernels: &[&str]) -> Experiment {
+        let list = |items: &[&str]| {
+            (!items.is_empty()).then(|| items.iter().map(|item| item.to_string()).collect())
+        };
+        Experiment {
+            containers: list(containers),
+            kernels: list(kernels),
+            ..Experiment::default()
+        }
+    }
+
+    #[test]
+    fn parses_every_setting() {
+        let experiment = parse(
+            r#"
+            [sizes]
+            start_pow = 10
+            end_pow = 26
+            step_pow = 2
+
+            [matrix]
+            groups = ["Sum of squares", "Sum of squares (f32)"]
+            containers = ["vec", "btreeset"]
+            kernels = ["by reference"]
+
+            [criterion]
+            sample_size = 100
+            measurement_time = 5.0
+            warm_up_time = 3
+            throughput = "elements"
+
+            [data]
+            seed = 24301
+            "#,
+        );
+
+        assert_eq!(
+            (
+                experiment.start_pow,
+                experiment.end_pow,
+                experiment.step_pow
+            ),
+            (Some(10), Some(26), Some(2))
+        );
+        assert_eq!(
+            experiment.groups.unwrap(),
+            ["Sum of squares", "Sum of squares (f32)"]
+        );
+        assert_eq!(experiment.containers.unwrap(), ["vec", "btreeset"]);
+        assert_eq!(experiment.kernels.unwrap(), ["by reference"]);
+        assert_eq!(experiment.sample_size, Some(100));
+        assert_eq!(experiment.measurement_time, Some(Duration::from_secs(5)));
+        assert_eq!(experiment.warm_up_time, Some(Duration::from_secs(3)));
+        assert_eq!(experiment.throughput, ThroughputUnit::Elements);
+        assert_eq!(experiment.seed, Some(24301));
+    }
+
+    #[test]
+    fn leaves_missing_settings_to_the_defaults() {
+        let experiment = parse("");
+        assert_eq!(experiment.start_pow, None);
+        assert!(experiment.groups.is_none());
+        assert_eq!(experiment.sample_size, None);
+        assert_eq!(experiment.throughput, ThroughputUnit::Bytes);
+        assert_eq!(experiment.seed, None);
+
+        let experiment = parse("[sizes]\nend_pow = 20\n");
+        assert_eq!((experiment.start_pow, experiment.end_pow), (None, Some(20)));
+    }
+
+    #[test]
+    fn rejects_malformed_experiments() {
+        for toml in [
+            "[sizes",
+            "sizes = 10",
+            "[sizes]\nstart_pow = -1",
+            "[sizes]\nend_pow = \"26\"",
+            "[sizes]\nstep_pow = 2.5",
+            "[matrix]\ngroups = \"Sum of squares\"",
+            "[matrix]\ncontainers = [\"vec\", 1]",
+            "[criterion]\nsample_size = 9",
+            "[criterion]\nmeasurement_time = 0",
+            "[criterion]\nwarm_up_time = -1.0",
+            "[criterion]\nwarm_up_time = nan",
+            "[criterion]\nthroughput = \"bits\"",
+            "[criterion]\nthroughput = 1",
+            "[data]\nseed = -5",
+        ] {
+            assert!(Experiment::parse(toml).is_err(), "{:?}", toml);
+        }
+    }
+
+    #[test]
+    fn selects_every_case_by_default() {
+        let experiment = Experiment::default();
+        assert!(experiment.selects_group("Sum of squares"));
+        assert!(experiment.selects("Vec (by reference)"));
+        assert!(experiment.selects("Vec"));
+        assert_eq!(experiment.group_filter(), None);
+    }
+
+    #[test]
+    fn selects_groups_by_their_exact_name() {
+        let experiment = Experiment {
+            groups: Some(vec!["Sum of squares (f32)".to_string()]),
+            ..Experiment::default()
+        };
+        assert!(experiment.selects_group("Sum of squares (f32)"));
+        assert!(!experiment.selects_group("Sum of squares"));
+        assert!(!experiment.selects_group("Sum of squares (f32) "));
+
+        let filter = regex::Regex::new(&experiment.group_filter().unwrap()).unwrap();
+        assert!(filter.is_match("Sum of squares (f32)/Vec (by reference)/1 kB"));
+        assert!(!filter.is_match("Sum of squares/Vec (by reference)/1 kB"));
+        assert!(!filter.is_match("Sum of squares (f32x)/Vec/1 kB"));
+    }
+
+    #[test]
+    fn selects_cases_by_container_and_kernel() {
+        let cases = [
+            // Case, containers, kernels, selected
+            ("Vec (by reference)", &["vec"][..], &[][..], true),
+            ("VecDeque (by reference)", &["vec"], &[], false),
+            ("SmallVec<16> (by value)", &["smallvec"], &[], true),
+            ("SmallVec<1024> (by value)", &["SmallVec<1024>"], &[], true),
+            ("SmallVec<16> (by value)", &["smallvec<1024>"], &[], false),
+            (
+                "hashbrown::HashSet (by value)",
+                &["hashbrown::hashset"],
+                &[],
+                true,
+            ),
+            ("hashbrown::HashSet (by value)", &["hashset"], &[], false),
+            ("Vec (by reference)", &[], &["By-Reference"], true),
+            ("Vec (by value)", &[], &["by reference"], false),
+            ("Vec", &[], &["by reference"], false),
+            ("Vec", &["vec"], &[], true),
+            ("Vec (by value)", &["btreeset", "vec"], &["by value"], true),
+            (
+                "BTreeSet (by reference)",
+                &["btreeset", "vec"],
+                &["by value"],
+                false,
+            ),
+            // Variants of a case are selected with it
+            ("Vec (by value) [cold]", &["vec"], &["by value"], true),
+            ("Vec (by value) [alloc 2]", &["vecdeque"], &[], false),
+        ];
+        for (ds_name, containers, kernels, selected) in cases {
+            assert_eq!(
+                selecting(containers, kernels).selects(ds_name),
+                selected,
+                "{:?} with {:?} and {:?}",
+                ds_name,
+                containers,
+                kernels
+            );
+        }
+    }
+
+    #[test]
+    fn splits_lists_skipping_empty_items() {
+        assert_eq!(split_list(" vec, btreeset ,,"), ["vec", "btreeset"]);
+        assert!(split_list("").is_empty());
+    }
+}
@@ -0,0 +1,85 @@
+//! Policies for non-finite values in the input.
+//!
+//! The plain kernels propagate NaN and infinity to the sum like any other
+//! value. The variants here skip them or stop at the first one, at the cost of
+//! checking every value.
+
+use crate::{Element, Primitive};
+use std::{error, fmt, iter};
+
+/// What a kernel does with NaN and infinite values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Policy {
+    /// Sum them like any other value, so that the sum is non-finite too.
+    Propagate,
+    /// Leave them out of the sum.
+    Skip,
+    /// Stop at the first one with an error.
+    Error,
+}
+
+impl Policy {
+    pub const ALL: [Policy; 3] = [Policy::Propagate, Policy::Skip, Policy::Error];
+}
+
+impl fmt::Display for Policy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Policy::Propagate => write!(f, "propagate"),
+            Policy::Skip => write!(f, "skip"),
+            Policy::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A non-finite value found by [`try_sum_of_squares`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NonFinite {
+    /// Position of the value in iteration order.
+    pub index: usize,
+}
+
+impl fmt::Display for NonFinite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "non-finite value at index {}", self.index)
+    }
+}
+
+impl error::Error for NonFinite {}
+
+/// Sum the square of each finite value, leaving out NaN and infinities.
+pub fn sum_of_squares_skipping_non_finite<V, T, P>(collection: &T) -> P
+where
+    T: ?Sized,
+    V: Element<P>,
+    P: Primitive,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a V>,
+{
+    collection
+        .into_iter()
+        .map(|x| x.inner())
+        .filter(|x| x.is_finite())
+        .map(|x| x.powi(2))
+        .sum::<P>()
+}
+
+/// Sum the square of each value, or fail at the first NaN or infinity.
+pub fn try_sum_of_squares<V, T, P>(collection: &T) -> Result<P, NonFinite>
+where
+    T: ?Sized,
+    V: Element<P>,
+    P: Primitive,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a V>,
+{
+    collection
+        .into_iter()
+        .enumerate()
+        .try_fold(P::zero(), |sum, (index, x)| {
+            let x = x.inner();
+            if x.is_finite() {
+                Ok(sum + x.powi(2))
+            } else {
+                Err(NonFinite { index })
+            }
+        })
+}
@@ -0,0 +1,37 @@
+//! Q-format fixed-point elements, for comparing against floating point on the
+//! same containers.
+//!
+//! Values are stored as Q32.32, a 64-bit integer with 32 fractional bits, so
+//! each element takes the same space as an `f64`.
+
+use crate::data;
+use rand::Rng;
+use std::iter;
+
+/// Q32.32 fixed-point element.
+pub type Q32 = fixed::types::I32F32;
+
+/// Create a data-structure of `n` fixed-point values, uniform over [0, 1) like
+/// [`crate::data::create_scrambled_data`].
+pub fn create_scrambled_fixed<T>(n: usize) -> T
+where
+    T: iter::FromIterator<Q32>,
+{
+    let mut rng = data::rng();
+
+    (0..n).map(|_| Q32::from_num(rng.gen::<f64>())).collect()
+}
+
+/// Sum the square of each value, referencing the data-structure immutably.
+///
+/// Squares of values in [0, 1) stay below one, so the sum only overflows past
+/// 2^31 elements; the arithmetic wraps around rather than panicking.
+pub fn sum_of_squares_fixed_by_ref<T>(collection: &T) -> Q32
+where
+    T: ?Sized,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a Q32>,
+{
+    collection
+        .into_iter()
+        .fold(Q32::ZERO, |sum, &x| sum.wrapping_add(x.wrapping_mul(x)))
+}
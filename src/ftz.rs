@@ -0,0 +1,82 @@
+//! Flush-to-zero and denormals-are-zero modes of the floating point unit.
+//!
+//! Arithmetic on subnormal values falls off the fast path on many CPUs and is
+//! handled by microcode, at up to a hundred times the latency. With the modes
+//! set, subnormal inputs are read as zero and subnormal results are written as
+//! zero, which keeps the fast path at the cost of the gradual underflow.
+//!
+//! The modes are per thread. On x86-64 they are the FTZ and DAZ bits of
+//! MXCSR, and on aarch64 the FZ bit of FPCR, which covers both.
+
+/// Whether the modes can be set on this target.
+pub fn supported() -> bool {
+    cfg!(any(target_arch = "x86_64", target_arch = "aarch64"))
+}
+
+/// Flushes subnormals to zero on the current thread until dropped, when the
+/// previous modes are restored.
+pub struct FlushToZero {
+    saved: u64,
+}
+
+impl FlushToZero {
+    /// Set the modes on the current thread, or `None` if they aren't
+    /// [`supported`].
+    pub fn enable() -> Option<Self> {
+        let saved = read_control()?;
+        write_control(saved | FLUSH_BITS);
+        Some(FlushToZero { saved })
+    }
+}
+
+impl Drop for FlushToZero {
+    fn drop(&mut self) {
+        write_control(self.saved);
+    }
+}
+
+// FTZ and DAZ
+#[cfg(target_arch = "x86_64")]
+const FLUSH_BITS: u64 = 1 << 15 | 1 << 6;
+// FZ
+#[cfg(target_arch = "aarch64")]
+const FLUSH_BITS: u64 = 1 << 24;
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+const FLUSH_BITS: u64 = 0;
+
+#[cfg(target_arch = "x86_64")]
+#[allow(deprecated)]
+fn read_control() -> Option<u64> {
+    // SAFETY: MXCSR is available on every x86-64 CPU
+    Some(unsafe { std::arch::x86_64::_mm_getcsr() } as u64)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[allow(deprecated)]
+fn write_control(control: u64) {
+    // SAFETY: the bits are those read from MXCSR, with FTZ and DAZ at most
+    // added, which every x86-64 CPU supports
+    unsafe { std::arch::x86_64::_mm_setcsr(control as u32) }
+}
+
+#[cfg(target_arch = "aarch64")]
+fn read_control() -> Option<u64> {
+    let fpcr: u64;
+    // SAFETY: FPCR is readable at every exception level
+    unsafe { std::arch::asm!("mrs {}, fpcr", out(reg) fpcr, options(nomem, nostack)) };
+    Some(fpcr)
+}
+
+#[cfg(target_arch = "aarch64")]
+fn write_control(control: u64) {
+    // SAFETY: the bits are those read from FPCR, with FZ at most added
+    unsafe { std::arch::asm!("msr fpcr, {}", in(reg) control, options(nomem, nostack)) };
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+fn read_control() -> Option<u64> {
+    None
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+fn write_control(_control: u64) {}
@@ -0,0 +1,173 @@
+//! Sum of squares on the GPU with a wgpu compute shader.
+//!
+//! WGSL has no 64-bit floats, so the GPU path works on `f32`. Uploading the
+//! input is kept separate from the reduction, so the benchmarks can measure it
+//! with and without the host to device transfer.
+
+use wgpu::util::DeviceExt;
+
+/// Number of workgroups dispatched, and so the number of partial sums read back.
+const WORKGROUPS: u32 = 256;
+
+/// A GPU device with the sum of squares pipeline ready to dispatch.
+pub struct Gpu {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+    partial_sums: wgpu::Buffer,
+    readback: wgpu::Buffer,
+}
+
+/// Input data resident in GPU memory.
+pub struct GpuBuffer {
+    buffer: wgpu::Buffer,
+    len: usize,
+}
+
+impl Gpu {
+    /// Open the default high-performance adapter, or `None` if there is no
+    /// usable GPU.
+    pub fn new() -> Option<Gpu> {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            force_fallback_adapter: false,
+            compatible_surface: None,
+        }))?;
+        let (device, queue) = pollster::block_on(adapter.request_device(
+            &wgpu::DeviceDescriptor {
+                label: None,
+                required_features: wgpu::Features::empty(),
+                // The largest buffers the adapter allows, rather than the
+                // portable defaults of 128 MiB bindings
+                required_limits: adapter.limits(),
+            },
+            None,
+        ))
+        .ok()?;
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("sum of squares"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("gpu/sum_of_squares.wgsl").into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("sum of squares"),
+            layout: None,
+            module: &shader,
+            entry_point: "main",
+        });
+
+        let partial_sums_size = u64::from(WORKGROUPS) * std::mem::size_of::<f32>() as u64;
+        let partial_sums = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("partial sums"),
+            size: partial_sums_size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("partial sums readback"),
+            size: partial_sums_size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Some(Gpu {
+            device,
+            queue,
+            pipeline,
+            partial_sums,
+            readback,
+        })
+    }
+
+    /// Largest input in bytes that fits in a buffer and a binding of the
+    /// device. [`Gpu::upload`] and [`Gpu::sum_of_squares`] panic on larger
+    /// inputs.
+    pub fn max_input_bytes(&self) -> u64 {
+        let limits = self.device.limits();
+        u64::from(limits.max_storage_buffer_binding_size).min(limits.max_buffer_size)
+    }
+
+    /// Copy `data` into GPU memory.
+    pub fn upload(&self, data: &[f32]) -> GpuBuffer {
+        let buffer = self
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("input"),
+                contents: bytemuck::cast_slice(data),
+                usage: wgpu::BufferUsages::STORAGE,
+            });
+
+        GpuBuffer {
+            buffer,
+            len: data.len(),
+        }
+    }
+
+    /// Sum the square of each value of an input already in GPU memory.
+    ///
+    /// Blocks until the partial sums have been read back, so the time includes
+    /// the dispatch and the device to host transfer of the partial sums.
+    pub fn sum_of_squares(&self, input: &GpuBuffer) -> f32 {
+        // Empty bindings are invalid, and there is nothing to sum anyway
+        if input.len == 0 {
+            return 0.0;
+        }
+
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &self.pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: input.buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: self.partial_sums.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: None,
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(WORKGROUPS, 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(
+            &self.partial_sums,
+            0,
+            &self.readback,
+            0,
+            self.partial_sums.size(),
+        );
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = self.readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |result| {
+            result.expect("could not map the partial sums")
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+
+        let sum = {
+            let mapped = slice.get_mapped_range();
+            bytemuck::cast_slice::<u8, f32>(&mapped).iter().sum::<f32>()
+        };
+        self.readback.unmap();
+
+        sum
+    }
+
+    /// Upload `data` and sum the square of each value, so the time includes the
+    /// host to device transfer.
+    pub fn sum_of_squares_with_transfer(&self, data: &[f32]) -> f32 {
+        self.sum_of_squares(&self.upload(data))
+    }
+}
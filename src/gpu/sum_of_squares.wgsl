@@ -0,0 +1,41 @@
+// Sum of squares reduction. Each workgroup strides over the input, reduces its
+// invocations' sums in workgroup memory, and writes one partial sum. The
+// partial sums are added up on the host.
+
+@group(0) @binding(0)
+var<storage, read> input: array<f32>;
+
+@group(0) @binding(1)
+var<storage, read_write> partial_sums: array<f32>;
+
+const WORKGROUP_SIZE: u32 = 256u;
+
+var<workgroup> scratch: array<f32, WORKGROUP_SIZE>;
+
+@compute @workgroup_size(WORKGROUP_SIZE)
+fn main(
+    @builtin(local_invocation_id) local_id: vec3<u32>,
+    @builtin(workgroup_id) group_id: vec3<u32>,
+    @builtin(num_workgroups) num_groups: vec3<u32>,
+) {
+    let stride = num_groups.x * WORKGROUP_SIZE;
+
+    var sum = 0.0;
+    for (var i = group_id.x * WORKGROUP_SIZE + local_id.x; i < arrayLength(&input); i += stride) {
+        let x = input[i];
+        sum += x * x;
+    }
+    scratch[local_id.x] = sum;
+    workgroupBarrier();
+
+    for (var offset = WORKGROUP_SIZE / 2u; offset > 0u; offset /= 2u) {
+        if (local_id.x < offset) {
+            scratch[local_id.x] += scratch[local_id.x + offset];
+        }
+        workgroupBarrier();
+    }
+
+    if (local_id.x == 0u) {
+        partial_sums[group_id.x] = scratch[0];
+    }
+}
@@ -0,0 +1,82 @@
+//! Array-of-structs versus struct-of-arrays layouts of multi-field records.
+//!
+//! Only the first field of each record is summed. Stored as an array of
+//! structs, every cache line also carries the `N - 1` unused fields; stored as
+//! a struct of arrays, the summed field is contiguous on its own.
+//!
+//! [`Padded`] elements isolate the same effect for the generic kernels: every
+//! value is followed by `N` bytes of padding.
+
+use crate::{data, Element, Inner};
+use float_ord::FloatOrd;
+use rand::Rng;
+use std::array;
+
+/// A value followed by `N` bytes of padding, so that only `8 / (8 + N)` of each
+/// cache line carries values.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Padded<const N: usize> {
+    v: FloatOrd<f64>,
+    _pad: [u8; N],
+}
+
+impl<const N: usize> Element<f64> for Padded<N> {}
+
+impl<const N: usize> Inner for Padded<N> {
+    type InnerType = f64;
+
+    fn inner(self) -> Self::InnerType {
+        self.v.0
+    }
+
+    fn create(inner: f64) -> Self {
+        Padded {
+            v: FloatOrd(inner),
+            _pad: [0; N],
+        }
+    }
+}
+
+/// A record of `N` fields, stored inline.
+#[derive(Clone, Copy, Debug)]
+pub struct Record<const N: usize> {
+    pub fields: [f64; N],
+}
+
+/// The fields of many records, one `Vec` per field.
+#[derive(Clone, Debug)]
+pub struct Columns<const N: usize> {
+    pub fields: [Vec<f64>; N],
+}
+
+impl<const N: usize> Columns<N> {
+    /// Transpose records into columns.
+    pub fn from_records(records: &[Record<N>]) -> Self {
+        Columns {
+            fields: array::from_fn(|field| records.iter().map(|r| r.fields[field]).collect()),
+        }
+    }
+}
+
+/// Create `n` records with uniformly random fields.
+pub fn create_scrambled_records<const N: usize>(n: usize) -> Vec<Record<N>> {
+    let mut rng = data::rng();
+
+    (0..n)
+        .map(|_| Record {
+            fields: array::from_fn(|_| rng.gen()),
+        })
+        .collect()
+}
+
+/// Sum the square of the first field of each record, stored as an array of
+/// structs.
+pub fn sum_of_squares_aos<const N: usize>(records: &[Record<N>]) -> f64 {
+    records.iter().map(|r| r.fields[0].powi(2)).sum::<f64>()
+}
+
+/// Sum the square of the first field of each record, stored as a struct of
+/// arrays.
+pub fn sum_of_squares_soa<const N: usize>(columns: &Columns<N>) -> f64 {
+    columns.fields[0].iter().map(|x| x.powi(2)).sum::<f64>()
+}
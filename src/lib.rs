@@ -1,12 +1,73 @@
+#![cfg_attr(feature = "simd", feature(portable_simd))]
+
 use float_ord::FloatOrd;
-use std::{hash, iter};
+use ordered_float::{NotNan, OrderedFloat};
+use std::{collections::VecDeque, hash, iter, ops};
 
-/// Something float-like, but orderable. P is backing primitive.
-pub trait Float<P>:
-    Copy + PartialEq + PartialOrd + Ord + Eq + hash::Hash + Inner<InnerType = P>
-{
-}
-impl Float<f64> for FloatOrd<f64> {}
+#[cfg(target_arch = "aarch64")]
+pub mod aarch64;
+pub mod accuracy;
+pub mod affinity;
+pub mod aligned;
+pub mod bandwidth;
+pub mod baseline;
+pub mod checksum;
+pub mod cpufreq;
+pub mod data;
+#[cfg(feature = "decimal")]
+pub mod decimal;
+pub mod evict;
+pub mod experiment;
+pub mod finite;
+#[cfg(feature = "fixed")]
+pub mod fixed_point;
+pub mod ftz;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+pub mod layout;
+pub mod list;
+#[cfg(any(target_arch = "x86_64", all(feature = "perf", target_os = "linux")))]
+mod measurement;
+pub mod memory;
+pub mod metadata;
+pub mod mmap;
+pub mod parallel;
+#[cfg(all(feature = "perf", target_os = "linux"))]
+pub mod perf;
+pub mod quantized;
+#[cfg(all(feature = "rapl", target_os = "linux"))]
+pub mod rapl;
+pub mod replicas;
+pub mod residency;
+pub mod results;
+pub mod sampling;
+pub mod scaling;
+pub mod schedule;
+#[cfg(feature = "simd")]
+pub mod simd;
+pub mod simulate;
+pub mod sweep;
+pub mod thermal;
+pub mod topology;
+#[cfg(target_arch = "x86_64")]
+pub mod tsc;
+pub mod unroll;
+#[cfg(target_arch = "x86_64")]
+pub mod x86;
+
+/// Something float-like. P is backing primitive.
+///
+/// Enough for the sequence containers, which only iterate over their elements.
+pub trait Element<P>: Copy + PartialEq + PartialOrd + Inner<InnerType = P> {}
+
+/// Something float-like, but orderable and hashable, as required by the set
+/// containers. P is backing primitive.
+pub trait OrderedElement<P>: Element<P> + Ord + Eq + hash::Hash {}
+impl<P, V: Element<P> + Ord + Eq + hash::Hash> OrderedElement<P> for V {}
+
+/// Primitive floating point type that squares are accumulated in.
+pub trait Primitive: num_traits::Float + ops::AddAssign + iter::Sum {}
+impl<P: num_traits::Float + ops::AddAssign + iter::Sum> Primitive for P {}
 
 pub trait Inner {
     type InnerType;
@@ -16,32 +77,157 @@ pub trait Inner {
     fn create(inner: Self::InnerType) -> Self;
 }
 
-impl Inner for FloatOrd<f64> {
-    type InnerType = f64;
+macro_rules! impl_primitive {
+    ($($primitive:ty),+) => {
+        $(
+            impl Element<$primitive> for $primitive {}
+
+            impl Inner for $primitive {
+                type InnerType = $primitive;
+
+                fn inner(self) -> Self::InnerType {
+                    self
+                }
+
+                fn create(inner: $primitive) -> Self {
+                    inner
+                }
+            }
+        )+
+    };
+}
+
+impl_primitive!(f32, f64);
+
+/// The bit pattern of a primitive, widened to 64 bits.
+pub trait Bits {
+    fn bits(self) -> u64;
+}
 
-    fn inner(self) -> Self::InnerType {
-        self.0
+impl Bits for f32 {
+    fn bits(self) -> u64 {
+        u64::from(self.to_bits())
     }
+}
 
-    fn create(inner: f64) -> Self {
-        FloatOrd::<f64>(inner)
+impl Bits for f64 {
+    fn bits(self) -> u64 {
+        self.to_bits()
     }
 }
 
+macro_rules! impl_float_ord {
+    ($($primitive:ty),+) => {
+        $(
+            impl Element<$primitive> for FloatOrd<$primitive> {}
+
+            impl Inner for FloatOrd<$primitive> {
+                type InnerType = $primitive;
+
+                fn inner(self) -> Self::InnerType {
+                    self.0
+                }
+
+                fn create(inner: $primitive) -> Self {
+                    FloatOrd::<$primitive>(inner)
+                }
+            }
+        )+
+    };
+}
+
+impl_float_ord!(f32, f64);
+
+macro_rules! impl_ordered_float {
+    ($($primitive:ty),+) => {
+        $(
+            impl Element<$primitive> for OrderedFloat<$primitive> {}
+
+            impl Inner for OrderedFloat<$primitive> {
+                type InnerType = $primitive;
+
+                fn inner(self) -> Self::InnerType {
+                    self.into_inner()
+                }
+
+                fn create(inner: $primitive) -> Self {
+                    OrderedFloat::<$primitive>(inner)
+                }
+            }
+
+            impl Element<$primitive> for NotNan<$primitive> {}
+
+            impl Inner for NotNan<$primitive> {
+                type InnerType = $primitive;
+
+                fn inner(self) -> Self::InnerType {
+                    self.into_inner()
+                }
+
+                /// Panics if `inner` is NaN.
+                fn create(inner: $primitive) -> Self {
+                    NotNan::new(inner).expect("NotNan created from NaN")
+                }
+            }
+        )+
+    };
+}
+
+impl_ordered_float!(f32, f64);
+
+/// Something integer-like, the counterpart of [`OrderedElement`] for integer
+/// elements.
+/// P is backing primitive.
+pub trait Int<P>:
+    Copy + PartialEq + PartialOrd + Ord + Eq + hash::Hash + Inner<InnerType = P>
+{
+}
+
+/// Primitive integer type that squares are accumulated in. Squares and sums
+/// wrap around on overflow, as they would in a release build.
+pub trait IntPrimitive:
+    num_traits::PrimInt + num_traits::WrappingAdd + num_traits::WrappingMul
+{
+}
+impl<P> IntPrimitive for P where
+    P: num_traits::PrimInt + num_traits::WrappingAdd + num_traits::WrappingMul
+{
+}
+
+macro_rules! impl_int {
+    ($($primitive:ty),+) => {
+        $(
+            impl Int<$primitive> for $primitive {}
+
+            impl Inner for $primitive {
+                type InnerType = $primitive;
+
+                fn inner(self) -> Self::InnerType {
+                    self
+                }
+
+                fn create(inner: $primitive) -> Self {
+                    inner
+                }
+            }
+        )+
+    };
+}
+
+impl_int!(i32, i64);
+
 /// Sum the square of each input value, taking ownership of the data-structure.
 ///
 /// Takes ownership of a collection, transforms it into an iterator and maps
 /// over the iterator, squaring each input element. The subsequent iterator is
 /// then accumulated to a single 'sum' value.
-pub fn sum_of_squares_by_move<V, T>(collection: T) -> f64
+pub fn sum_of_squares_by_move<V, T, P>(collection: T) -> P
 where
-    V: Float<f64>,
+    V: Element<P>,
+    P: Primitive,
     T: iter::IntoIterator<Item = V>,
 {
-    collection
-        .into_iter()
-        .map(|x| x.inner().powi(2))
-        .sum::<f64>()
+    collection.into_iter().map(|x| x.inner().powi(2)).sum::<P>()
 }
 
 /// Sum the square of each input value, referencing the data-structure
@@ -51,13 +237,337 @@ where
 /// iterator over references to the original values in collection. This iterator
 /// is mapped to produce the square of each input value. The subsequent iterator
 /// is then accumulated to a single 'sum' value.
-pub fn sum_of_squares_by_ref<V, T>(collection: &T) -> f64
+pub fn sum_of_squares_by_ref<V, T, P>(collection: &T) -> P
+where
+    T: ?Sized,
+    V: Element<P>,
+    P: Primitive,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a V>,
+{
+    collection.into_iter().map(|x| x.inner().powi(2)).sum::<P>()
+}
+
+/// Fold the bits of the values of a collection together with xor.
+///
+/// The same traversal as [`sum_of_squares_by_ref`] without any floating point
+/// arithmetic on the values, so that the difference between the two is the
+/// cost of the arithmetic. Only the result goes through `black_box`: the xor
+/// is about as cheap as an operation gets, yet needs every value loaded.
+pub fn traverse<V, T, P>(collection: &T) -> u64
+where
+    T: ?Sized,
+    V: Element<P>,
+    P: Bits,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a V>,
+{
+    let bits = collection
+        .into_iter()
+        .fold(0, |bits, &x| bits ^ x.inner().bits());
+
+    criterion::black_box(bits)
+}
+
+/// Sum the square of each value in a slice.
+///
+/// Same as [`sum_of_squares_by_ref`] on a bare slice, without spelling out the
+/// higher-ranked `IntoIterator` bound.
+pub fn sum_of_squares_slice<V, P>(data: &[V]) -> P
+where
+    V: Element<P>,
+    P: Primitive,
+{
+    data.iter().map(|x| x.inner().powi(2)).sum::<P>()
+}
+
+/// Sum the square of each value in a slice of plain `f64`s.
+pub fn sum_of_squares_slice_f64(data: &[f64]) -> f64 {
+    data.iter().map(|x| x.powi(2)).sum::<f64>()
+}
+
+/// Sum the square of each value in a slice of `f32`s in single precision.
+pub fn sum_of_squares_slice_f32(data: &[f32]) -> f32 {
+    data.iter().map(|x| x.powi(2)).sum::<f32>()
+}
+
+/// Sum the square of each value in a slice of `f32`s, widening each value to
+/// `f64` before squaring and accumulating.
+///
+/// Reads half the bytes of [`sum_of_squares_slice_f64`] for the same number of
+/// values, with the accuracy of a double-precision accumulator.
+pub fn sum_of_squares_slice_f32_widening(data: &[f32]) -> f64 {
+    data.iter().map(|&x| f64::from(x).powi(2)).sum::<f64>()
+}
+
+/// Sum the square of each value in a slice of `f16`s, widening each value to
+/// `f32` before squaring and accumulating.
+#[cfg(feature = "half")]
+pub fn sum_of_squares_slice_f16_widening_f32(data: &[half::f16]) -> f32 {
+    data.iter().map(|&x| f32::from(x).powi(2)).sum::<f32>()
+}
+
+/// Sum the square of each value in a slice of `f16`s, widening each value to
+/// `f64` before squaring and accumulating.
+///
+/// Reads a quarter of the bytes of [`sum_of_squares_slice_f64`] for the same
+/// number of values.
+#[cfg(feature = "half")]
+pub fn sum_of_squares_slice_f16_widening(data: &[half::f16]) -> f64 {
+    data.iter().map(|&x| f64::from(x).powi(2)).sum::<f64>()
+}
+
+/// Sum the square of each input value with an explicit `fold`, referencing the
+/// data-structure immutably.
+///
+/// Functionally identical to [`sum_of_squares_by_ref`], which uses `sum`.
+pub fn sum_of_squares_fold<V, T, P>(collection: &T) -> P
 where
-    V: Float<f64>,
+    T: ?Sized,
+    V: Element<P>,
+    P: Primitive,
     for<'a> &'a T: iter::IntoIterator<Item = &'a V>,
 {
     collection
         .into_iter()
-        .map(|x| x.inner().powi(2))
-        .sum::<f64>()
+        .fold(P::zero(), |sum, x| sum + x.inner().powi(2))
+}
+
+/// Sum the square of each input value with a hand-written loop that calls
+/// `next` on the iterator, referencing the data-structure immutably.
+///
+/// Functionally identical to [`sum_of_squares_by_ref`], but bypasses any
+/// specialized `fold` the container's iterator may implement.
+#[allow(clippy::while_let_on_iterator)]
+pub fn sum_of_squares_while<V, T, P>(collection: &T) -> P
+where
+    T: ?Sized,
+    V: Element<P>,
+    P: Primitive,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a V>,
+{
+    let mut iter = collection.into_iter();
+    let mut sum = P::zero();
+    while let Some(x) = iter.next() {
+        sum += x.inner().powi(2);
+    }
+    sum
+}
+
+/// Sum the square of each value yielded by a type-erased iterator.
+///
+/// Every `next` is a virtual call through the trait object, so the loop
+/// cannot be inlined into the iterator or vectorized, unlike the
+/// monomorphized [`sum_of_squares_by_ref`].
+pub fn sum_of_squares_dyn(values: Box<dyn Iterator<Item = f64> + '_>) -> f64 {
+    values.map(|x| x.powi(2)).sum::<f64>()
+}
+
+/// Sum the square of each input value using Kahan's compensated summation,
+/// referencing the data-structure immutably.
+///
+/// Same traversal as [`sum_of_squares_by_ref`], but carries a running
+/// compensation term that recovers the low-order bits lost by each addition.
+pub fn sum_of_squares_kahan<V, T, P>(collection: &T) -> P
+where
+    T: ?Sized,
+    V: Element<P>,
+    P: Primitive,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a V>,
+{
+    let (sum, _compensation) = collection.into_iter().map(|x| x.inner().powi(2)).fold(
+        (P::zero(), P::zero()),
+        |(sum, compensation), x| {
+            let y = x - compensation;
+            let t = sum + y;
+            (t, (t - sum) - y)
+        },
+    );
+    sum
+}
+
+/// A collection with a length, for index-based loops over `0..len`.
+pub trait Len {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<V> Len for [V] {
+    fn len(&self) -> usize {
+        <[V]>::len(self)
+    }
+}
+
+impl<V> Len for Vec<V> {
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+}
+
+impl<V> Len for VecDeque<V> {
+    fn len(&self) -> usize {
+        VecDeque::len(self)
+    }
+}
+
+/// Sum the square of each input value, looping over `0..len` and indexing
+/// into the collection.
+///
+/// Same arithmetic as [`sum_of_squares_by_ref`], but every access goes through
+/// `Index`, so whether the bounds checks are elided is up to the optimizer.
+pub fn sum_of_squares_indexed<V, T, P>(collection: &T) -> P
+where
+    T: ?Sized + Len + ops::Index<usize, Output = V>,
+    V: Element<P>,
+    P: Primitive,
+{
+    let mut sum = P::zero();
+    for i in 0..collection.len() {
+        sum += collection[i].inner().powi(2);
+    }
+    sum
+}
+
+/// Number of elements below which [`sum_of_squares_pairwise`] stops splitting
+/// and sums sequentially.
+const PAIRWISE_BLOCK_LEN: usize = 8;
+
+/// Sum the square of each input value using pairwise (cascade) summation.
+///
+/// Recursively splits the slice in half and adds the two partial sums, which
+/// bounds the rounding error growth to O(log n) instead of O(n).
+pub fn sum_of_squares_pairwise<V, P>(data: &[V]) -> P
+where
+    V: Element<P>,
+    P: Primitive,
+{
+    if data.len() <= PAIRWISE_BLOCK_LEN {
+        data.iter().map(|x| x.inner().powi(2)).sum::<P>()
+    } else {
+        let (left, right) = data.split_at(data.len() / 2);
+        sum_of_squares_pairwise(left) + sum_of_squares_pairwise(right)
+    }
+}
+
+/// Sum the square of each value in a slice block by block.
+///
+/// Each block of `chunk_len` values is reduced to a partial sum before the
+/// partial sums are combined, so a block can be sized to stay resident in a
+/// given cache level.
+pub fn sum_of_squares_chunked<V, P>(data: &[V], chunk_len: usize) -> P
+where
+    V: Element<P>,
+    P: Primitive,
+{
+    data.chunks(chunk_len)
+        .map(|chunk| chunk.iter().map(|x| x.inner().powi(2)).sum::<P>())
+        .sum::<P>()
+}
+
+/// Format a size in bytes with a binary unit suffix, e.g. "64 kB". Sizes that
+/// aren't a whole number of units get one decimal, e.g. "14.4 MB".
+pub fn human_readable_size(size_bytes: usize) -> String {
+    let in_unit = |unit: u64, suffix: &str| {
+        let whole = size_bytes as u64 / unit;
+        if whole * unit == size_bytes as u64 {
+            whole.to_string() + suffix
+        } else {
+            format!("{:.1}{}", size_bytes as f64 / unit as f64, suffix)
+        }
+    };
+
+    if size_bytes < 1024 {
+        size_bytes.to_string() + " bytes"
+    } else if size_bytes < 1024 * 1024 {
+        in_unit(1024, " kB")
+    } else if size_bytes < 1024 * 1024 * 1024 {
+        in_unit(1024 * 1024, " MB")
+    } else if (size_bytes as u64) < 1024 * 1024 * 1024 * 1024 {
+        in_unit(1024 * 1024 * 1024, " GB")
+    } else {
+        size_bytes.to_string() + " ??"
+    }
+}
+
+/// Sum the square of each byte, widening to a 64-bit accumulator.
+///
+/// Generic over any collection of bytes, like [`sum_of_squares_by_ref`], so the
+/// widening happens one element at a time.
+pub fn sum_of_squares_u8_by_ref<T>(collection: &T) -> u64
+where
+    T: ?Sized,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a u8>,
+{
+    collection
+        .into_iter()
+        .map(|&x| u64::from(x) * u64::from(x))
+        .sum::<u64>()
+}
+
+/// Sum the square of each integer, taking ownership of the data-structure.
+///
+/// Same traversal as [`sum_of_squares_by_move`], with wrapping arithmetic so
+/// that the result is well-defined for any input.
+pub fn sum_of_squares_int_by_move<V, T, P>(collection: T) -> P
+where
+    V: Int<P>,
+    P: IntPrimitive,
+    T: iter::IntoIterator<Item = V>,
+{
+    collection.into_iter().fold(P::zero(), |sum, x| {
+        let x = x.inner();
+        sum.wrapping_add(&x.wrapping_mul(&x))
+    })
+}
+
+/// Sum the square of each integer, referencing the data-structure immutably.
+///
+/// Same traversal as [`sum_of_squares_by_ref`], with wrapping arithmetic so
+/// that the result is well-defined for any input.
+pub fn sum_of_squares_int_by_ref<V, T, P>(collection: &T) -> P
+where
+    T: ?Sized,
+    V: Int<P>,
+    P: IntPrimitive,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a V>,
+{
+    collection.into_iter().fold(P::zero(), |sum, &x| {
+        let x = x.inner();
+        sum.wrapping_add(&x.wrapping_mul(&x))
+    })
+}
+
+/// Sum the squared magnitude `|z|²` of each complex value, referencing the
+/// data-structure immutably.
+///
+/// Each element is twice the size of an `f64` and takes two multiplies and two
+/// adds, instead of one of each.
+pub fn sum_of_squares_complex_by_ref<T>(collection: &T) -> f64
+where
+    T: ?Sized,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a num_complex::Complex<f64>>,
+{
+    collection.into_iter().map(|z| z.norm_sqr()).sum::<f64>()
+}
+
+/// Number of bytes whose squares are guaranteed to fit in a `u32` partial sum:
+/// 255² × 65_536 < 2^32.
+const U8_WIDENING_BLOCK_LEN: usize = 65_536;
+
+/// Sum the square of each byte in a slice, widening in two steps.
+///
+/// Each block is summed into a `u32`, which LLVM can vectorize with the
+/// u8 → u16 → u32 widening multiplies. Only the per-block partial sums are
+/// widened to the final `u64`.
+pub fn sum_of_squares_u8_widening(data: &[u8]) -> u64 {
+    data.chunks(U8_WIDENING_BLOCK_LEN)
+        .map(|block| {
+            block
+                .iter()
+                .map(|&x| u32::from(x) * u32::from(x))
+                .sum::<u32>()
+        })
+        .map(u64::from)
+        .sum::<u64>()
 }
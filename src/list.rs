@@ -0,0 +1,125 @@
+//! Linked lists with a controlled memory layout.
+//!
+//! `std::collections::LinkedList` allocates every node separately, so its
+//! traversal cost mixes pointer chasing with wherever the allocator happened
+//! to put the nodes. These lists keep the pointer chasing but control the
+//! layout.
+
+use arrayvec::ArrayVec;
+use std::{
+    collections::{linked_list, LinkedList},
+    iter, mem,
+};
+use typed_arena::Arena;
+
+/// A node of an [`ArenaList`].
+pub struct ArenaNode<'a, V> {
+    value: V,
+    next: Option<&'a ArenaNode<'a, V>>,
+}
+
+/// A singly linked list whose nodes are allocated from a typed arena in one
+/// contiguous run, so that consecutive nodes are adjacent in memory.
+pub struct ArenaList<'a, V> {
+    head: Option<&'a ArenaNode<'a, V>>,
+}
+
+impl<'a, V> ArenaList<'a, V> {
+    /// Build a list of `values` in their iteration order, allocating the nodes
+    /// from `arena`.
+    pub fn from_iter_in<I>(arena: &'a Arena<ArenaNode<'a, V>>, values: I) -> Self
+    where
+        I: IntoIterator<Item = V>,
+    {
+        let mut nodes = arena.alloc_extend(
+            values
+                .into_iter()
+                .map(|value| ArenaNode { value, next: None }),
+        );
+
+        // Link from the back, so that each node is finished before it is
+        // borrowed by its predecessor
+        let mut next = None;
+        while let Some((last, init)) = nodes.split_last_mut() {
+            last.next = next;
+            next = Some(&*last);
+            nodes = init;
+        }
+
+        ArenaList { head: next }
+    }
+
+    pub fn iter(&self) -> ArenaIter<'a, V> {
+        ArenaIter { next: self.head }
+    }
+}
+
+/// Iterator over the values of an [`ArenaList`].
+pub struct ArenaIter<'a, V> {
+    next: Option<&'a ArenaNode<'a, V>>,
+}
+
+impl<'a, V> Iterator for ArenaIter<'a, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.next?;
+        self.next = node.next;
+        Some(&node.value)
+    }
+}
+
+impl<'l, V> IntoIterator for &'l ArenaList<'_, V> {
+    type Item = &'l V;
+    type IntoIter = ArenaIter<'l, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// A linked list of fixed-capacity nodes holding up to `N` values each.
+///
+/// With `N = 1` this is an ordinary linked list, and as `N` grows the layout
+/// approaches a contiguous `Vec`, with one pointer per `N` values.
+#[derive(Clone)]
+pub struct UnrolledList<V, const N: usize> {
+    nodes: LinkedList<ArrayVec<V, N>>,
+}
+
+impl<V, const N: usize> iter::FromIterator<V> for UnrolledList<V, N> {
+    fn from_iter<I: IntoIterator<Item = V>>(values: I) -> Self {
+        let mut nodes = LinkedList::new();
+        let mut node = ArrayVec::new();
+
+        for value in values {
+            node.push(value);
+            if node.is_full() {
+                nodes.push_back(mem::take(&mut node));
+            }
+        }
+        if !node.is_empty() {
+            nodes.push_back(node);
+        }
+
+        UnrolledList { nodes }
+    }
+}
+
+impl<V, const N: usize> IntoIterator for UnrolledList<V, N> {
+    type Item = V;
+    type IntoIter = iter::Flatten<linked_list::IntoIter<ArrayVec<V, N>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.nodes.into_iter().flatten()
+    }
+}
+
+impl<'l, V, const N: usize> IntoIterator for &'l UnrolledList<V, N> {
+    type Item = &'l V;
+    type IntoIter = iter::Flatten<linked_list::Iter<'l, ArrayVec<V, N>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.nodes.iter().flatten()
+    }
+}
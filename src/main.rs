@@ -1,13 +1,13 @@
 /*
 use float_ord::FloatOrd;
 use rand::Rng;
-use spp_experiments::{sum_of_squares_by_move, sum_of_squares_by_ref, Float};
+use spp_experiments::{sum_of_squares_by_move, sum_of_squares_by_ref, Element};
 use std::iter;
 
 /// Create the concrete data-structure of length `n` using FromIterator<V> where V is the element type.
 fn create_scrambled_data<V, T>(n: usize) -> T
 where
-    V: Float<f64>,
+    V: Element<f64>,
     T: iter::FromIterator<V>,
 {
     let mut rng = rand::thread_rng();
@@ -34,4 +34,397 @@ fn main() {
 }
 
 */
-fn main() {}
+use criterion::black_box;
+use float_ord::FloatOrd;
+use indexmap::IndexSet;
+use smallvec::SmallVec;
+use spp_experiments::{
+    accuracy, bandwidth, baseline,
+    data::{create_distinct_data, Distribution, Order},
+    experiment::Experiment,
+    human_readable_size, replicas, results, simulate, sum_of_squares_by_move,
+    sum_of_squares_by_ref,
+    sweep::Sweep,
+    topology,
+};
+use std::collections::{BTreeSet, BinaryHeap, HashSet, LinkedList, VecDeque};
+use std::{env, iter, process};
+
+/// The sweep of the benchmarks, resolved as the bench binary does from the
+/// experiment, the environment and the detected caches.
+fn sweep() -> Sweep {
+    let experiment = Experiment::load().unwrap_or_else(|e| {
+        eprintln!("error: could not read the experiment: {}", e);
+        process::exit(1);
+    });
+    let capacities = topology::data_caches()
+        .map(|caches| {
+            caches
+                .iter()
+                .map(|cache| cache.size_bytes)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    Sweep::resolve(&experiment, &capacities).unwrap_or_else(|e| {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    })
+}
+
+fn print_accuracy() {
+    println!("size\tdistribution\torder\tkernel\tvalue\tabs_error\trel_error\trel_error_delta");
+
+    for input_size_bytes in sweep().sizes() {
+        for &distribution in Distribution::ALL.iter() {
+            let reports = accuracy::report(input_size_bytes, distribution, &Order::ALL);
+            // Deltas are relative to the scrambled order, which comes first
+            let scrambled = &reports[0];
+            for report in &reports {
+                for (error, baseline) in report.errors.iter().zip(&scrambled.errors) {
+                    println!(
+                        "{}\t{}\t{}\t{}\t{:e}\t{:e}\t{:e}\t{:e}",
+                        human_readable_size(report.input_size_bytes),
+                        report.distribution,
+                        report.order,
+                        error.kernel,
+                        error.value,
+                        error.abs_error,
+                        error.rel_error,
+                        error.rel_error - baseline.rel_error
+                    );
+                }
+            }
+        }
+    }
+}
+
+// Group and containers of the main benchmark matrix, named as in the benchmark
+// IDs
+const GROUP: &str = "Sum of squares";
+const CONTAINERS: [&str; 12] = [
+    "Vec",
+    "Box<[_]>",
+    "SmallVec<16>",
+    "SmallVec<1024>",
+    "VecDeque",
+    "im::Vector",
+    "LinkedList",
+    "BinaryHeap",
+    "HashSet",
+    "hashbrown::HashSet",
+    "IndexSet",
+    "BTreeSet",
+];
+const MODES: [(&str, &str); 2] = [("by-reference", "by reference"), ("by-value", "by value")];
+
+/// Build a container of `input_size_bytes` and run a single kernel over it, or
+/// only build it with `setup_only`.
+fn run_kernel(container: &str, mode: &str, input_size_bytes: usize, setup_only: bool) {
+    let data_len = input_size_bytes / std::mem::size_of::<f64>();
+    let sum = match container {
+        "Vec" => run_kernel_on::<Vec<FloatOrd<f64>>>(mode, data_len, setup_only),
+        "Box<[_]>" => run_kernel_on::<Box<[FloatOrd<f64>]>>(mode, data_len, setup_only),
+        "SmallVec<16>" => {
+            run_kernel_on::<SmallVec<[FloatOrd<f64>; 16]>>(mode, data_len, setup_only)
+        }
+        "SmallVec<1024>" => {
+            run_kernel_on::<SmallVec<[FloatOrd<f64>; 1024]>>(mode, data_len, setup_only)
+        }
+        "VecDeque" => run_kernel_on::<VecDeque<FloatOrd<f64>>>(mode, data_len, setup_only),
+        "im::Vector" => run_kernel_on::<im::Vector<FloatOrd<f64>>>(mode, data_len, setup_only),
+        "LinkedList" => run_kernel_on::<LinkedList<FloatOrd<f64>>>(mode, data_len, setup_only),
+        "BinaryHeap" => run_kernel_on::<BinaryHeap<FloatOrd<f64>>>(mode, data_len, setup_only),
+        "HashSet" => run_kernel_on::<HashSet<FloatOrd<f64>>>(mode, data_len, setup_only),
+        "hashbrown::HashSet" => {
+            run_kernel_on::<hashbrown::HashSet<FloatOrd<f64>>>(mode, data_len, setup_only)
+        }
+        "IndexSet" => run_kernel_on::<IndexSet<FloatOrd<f64>>>(mode, data_len, setup_only),
+        "BTreeSet" => run_kernel_on::<BTreeSet<FloatOrd<f64>>>(mode, data_len, setup_only),
+        _ => usage(),
+    };
+
+    println!("{}", sum);
+}
+
+fn run_kernel_on<T>(mode: &str, data_len: usize, setup_only: bool) -> f64
+where
+    T: iter::FromIterator<FloatOrd<f64>> + iter::IntoIterator<Item = FloatOrd<f64>>,
+    for<'a> &'a T: iter::IntoIterator<Item = &'a FloatOrd<f64>>,
+{
+    let data: T = create_distinct_data(data_len);
+
+    if setup_only {
+        black_box(&data);
+        return 0.0;
+    }
+    match mode {
+        "by-reference" => sum_of_squares_by_ref(black_box(&data)),
+        "by-value" => sum_of_squares_by_move(black_box(data)),
+        _ => usage(),
+    }
+}
+
+/// Run every point of the main matrix under cachegrind and store the kernel's
+/// counts in the simulated results namespace.
+fn simulate() {
+    let program = env::current_exe().expect("could not locate own executable");
+    let mut counts = Vec::new();
+
+    for input_size_bytes in sweep().sizes() {
+        for &container in CONTAINERS.iter() {
+            for &(mode, mode_name) in MODES.iter() {
+                let full_id = format!(
+                    "{}/{} ({})/{}",
+                    GROUP,
+                    container,
+                    mode_name,
+                    human_readable_size(input_size_bytes)
+                );
+                eprintln!("Simulating {}", full_id);
+
+                let args = vec![
+                    "kernel".to_string(),
+                    container.to_string(),
+                    mode.to_string(),
+                    input_size_bytes.to_string(),
+                ];
+                let mut setup_args = args.clone();
+                setup_args.push("--setup-only".to_string());
+
+                let simulated = simulate::run_cachegrind(&program, &args)
+                    .and_then(|total| {
+                        let setup = simulate::run_cachegrind(&program, &setup_args)?;
+                        Ok(total.saturating_sub(&setup))
+                    })
+                    .unwrap_or_else(|e| {
+                        eprintln!("error: {}", e);
+                        process::exit(1);
+                    });
+
+                for &(metric, value) in simulated.metrics().iter() {
+                    counts.push(results::SimulatedCount {
+                        full_id: full_id.clone(),
+                        metric: metric.to_string(),
+                        value,
+                    });
+                }
+            }
+        }
+    }
+
+    results::write_simulated(&counts).expect("could not write simulated results");
+}
+
+/// Print measured and simulated results side by side, per benchmark ID, with the
+/// elements per nanosecond of the benchmarks that reported their throughput in
+/// elements.
+fn print_report() {
+    let measured = results::read_measured().expect("could not read measured results");
+    let simulated = results::read_simulated().expect("could not read simulated results");
+
+    let metrics = simulate::CacheCounts::default().metrics();
+    print!(
+        "benchmark\t{}_mean_ns\t{}_elements_per_ns",
+        results::MEASURED,
+        results::MEASURED
+    );
+    for &(metric, _) in metrics.iter() {
+        print!("\t{}_{}", results::SIMULATED, metric);
+    }
+    println!();
+
+    for estimate in &measured {
+        print!("{}\t{:.1}", estimate.full_id, estimate.mean_ns);
+        match estimate.throughput_elements {
+            Some(elements) => print!("\t{:.3}", elements as f64 / estimate.mean_ns),
+            None => print!("\t-"),
+        }
+        for &(metric, _) in metrics.iter() {
+            match simulated
+                .iter()
+                .find(|count| count.full_id == estimate.full_id && count.metric == metric)
+            {
+                Some(count) => print!("\t{}", count.value),
+                None => print!("\t-"),
+            }
+        }
+        println!();
+    }
+}
+
+/// Print the achieved bandwidth of every measured result that reported its
+/// input size, against the peak bandwidth measured with the triad.
+fn print_bandwidth() {
+    let measured = results::read_measured().expect("could not read measured results");
+
+    eprintln!("Measuring peak bandwidth");
+    let peak = bandwidth::measure_peak();
+    println!("# peak (triad): {:.2} GB/s", peak * 1e-9);
+
+    println!("benchmark\tmean_ns\tgb_per_s\tfraction_of_peak\tbound");
+    for estimate in &measured {
+        let bytes = match estimate.throughput_bytes {
+            Some(bytes) => bytes,
+            None => continue,
+        };
+        let achieved = bandwidth::achieved(bytes, estimate.mean_ns);
+        let fraction = achieved / peak;
+        println!(
+            "{}\t{:.1}\t{:.2}\t{:.3}\t{}",
+            estimate.full_id,
+            estimate.mean_ns,
+            achieved * 1e-9,
+            fraction,
+            if fraction >= bandwidth::BOUND_FRACTION {
+                "bandwidth"
+            } else {
+                "-"
+            }
+        );
+    }
+}
+
+/// Print the variance between the allocations of every replicated benchmark
+/// against the variance within them.
+fn print_replicas() {
+    let measured = results::read_measured().expect("could not read measured results");
+
+    println!("benchmark\treplicas\tmean_ns\tbetween_sd_ns\twithin_sd_ns\tbetween_fraction");
+    for variance in replicas::variances(&measured) {
+        println!(
+            "{}\t{}\t{:.1}\t{:.1}\t{:.1}\t{:.3}",
+            variance.full_id,
+            variance.replicas,
+            variance.mean_ns,
+            variance.between.sqrt(),
+            variance.within.sqrt(),
+            variance.between_fraction()
+        );
+    }
+}
+
+/// Snapshot the latest measured results as the baseline called `name`.
+fn save_baseline(name: &str) {
+    let measured = results::read_measured().unwrap_or_else(|e| {
+        eprintln!("error: could not read measured results: {}", e);
+        process::exit(1);
+    });
+    if measured.is_empty() {
+        eprintln!("error: no measured results to save");
+        process::exit(1);
+    }
+
+    baseline::save(name, &measured).unwrap_or_else(|e| {
+        eprintln!("error: could not save baseline {}: {}", name, e);
+        process::exit(1);
+    });
+    eprintln!("Saved {} results as baseline {}", measured.len(), name);
+}
+
+/// Compare the latest measured results against the baseline called `name`,
+/// exiting with 1 if any benchmark is slower by more than `threshold`.
+fn compare_baseline(name: &str, threshold: f64) {
+    let base = baseline::read(name).unwrap_or_else(|e| {
+        eprintln!("error: could not read baseline {}: {}", name, e);
+        process::exit(1);
+    });
+    let measured = results::read_measured().unwrap_or_else(|e| {
+        eprintln!("error: could not read measured results: {}", e);
+        process::exit(1);
+    });
+
+    let comparison = baseline::compare(&base, &measured);
+    println!("benchmark\tbaseline_ns\tmean_ns\tchange\tstatus");
+    for change in &comparison.changes {
+        println!(
+            "{}\t{:.1}\t{:.1}\t{:+.1}%\t{}",
+            change.full_id,
+            change.baseline_ns,
+            change.mean_ns,
+            change.relative() * 100.0,
+            if change.regressed(threshold) {
+                "regressed"
+            } else {
+                "-"
+            }
+        );
+    }
+    for full_id in &comparison.missing {
+        println!("{}\t-\t-\t-\tmissing", full_id);
+    }
+    for full_id in &comparison.added {
+        println!("{}\t-\t-\t-\tnew", full_id);
+    }
+
+    let regressions = comparison
+        .changes
+        .iter()
+        .filter(|change| change.regressed(threshold))
+        .count();
+    eprintln!(
+        "{} of {} benchmarks regressed by more than {:.1}%",
+        regressions,
+        comparison.changes.len(),
+        threshold * 100.0
+    );
+    if !comparison.missing.is_empty() {
+        eprintln!(
+            "error: {} benchmarks of baseline {} weren't measured",
+            comparison.missing.len(),
+            name
+        );
+    }
+    if comparison.changes.is_empty() {
+        eprintln!("error: no measured benchmark matches baseline {}", name);
+    }
+    if regressions > 0 || !comparison.complete() {
+        process::exit(1);
+    }
+}
+
+fn usage() -> ! {
+    eprintln!(
+        "usage: spp_experiments_bin accuracy
+       spp_experiments_bin kernel <container> <by-reference|by-value> <size in bytes> [--setup-only]
+       spp_experiments_bin simulate
+       spp_experiments_bin report
+       spp_experiments_bin bandwidth
+       spp_experiments_bin replicas
+       spp_experiments_bin baseline save <name>
+       spp_experiments_bin baseline compare <name> [--threshold <percent>]"
+    );
+    process::exit(2);
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+
+    match args.as_slice() {
+        ["accuracy"] => print_accuracy(),
+        ["kernel", container, mode, size] => run_kernel(
+            container,
+            mode,
+            size.parse().unwrap_or_else(|_| usage()),
+            false,
+        ),
+        ["kernel", container, mode, size, "--setup-only"] => run_kernel(
+            container,
+            mode,
+            size.parse().unwrap_or_else(|_| usage()),
+            true,
+        ),
+        ["simulate"] => simulate(),
+        ["report"] => print_report(),
+        ["bandwidth"] => print_bandwidth(),
+        ["replicas"] => print_replicas(),
+        ["baseline", "save", name] => save_baseline(name),
+        ["baseline", "compare", name] => compare_baseline(name, baseline::DEFAULT_THRESHOLD),
+        ["baseline", "compare", name, "--threshold", percent] => compare_baseline(
+            name,
+            percent.parse::<f64>().unwrap_or_else(|_| usage()) / 100.0,
+        ),
+        _ => usage(),
+    }
+}
@@ -0,0 +1,39 @@
+//! Shared parts of the criterion measurements that count events or cycles
+//! instead of wall time.
+
+use criterion::{measurement::ValueFormatter, Throughput};
+
+/// Formats event counts without scaling, and throughputs as counts per byte or
+/// per element.
+pub(crate) struct CountFormatter {
+    pub(crate) unit: &'static str,
+    pub(crate) per_byte: &'static str,
+    pub(crate) per_element: &'static str,
+}
+
+impl ValueFormatter for CountFormatter {
+    fn scale_values(&self, _typical_value: f64, _values: &mut [f64]) -> &'static str {
+        self.unit
+    }
+
+    fn scale_throughputs(
+        &self,
+        _typical_value: f64,
+        throughput: &Throughput,
+        values: &mut [f64],
+    ) -> &'static str {
+        let (n, unit) = match *throughput {
+            Throughput::Bytes(n) => (n, self.per_byte),
+            Throughput::Elements(n) => (n, self.per_element),
+        };
+        for value in values {
+            *value /= n as f64;
+        }
+
+        unit
+    }
+
+    fn scale_for_machines(&self, _values: &mut [f64]) -> &'static str {
+        self.unit
+    }
+}
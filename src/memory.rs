@@ -0,0 +1,69 @@
+//! Available memory and the estimated footprint of the containers.
+//!
+//! Inputs past the size of the RAM get the process OOM-killed halfway through
+//! a run, so cases whose input wouldn't fit are skipped instead. The footprint
+//! of a container is modelled from its layout, as building it to find out is
+//! exactly what must be avoided.
+
+use std::{fs, io};
+
+/// Share of the available memory a case may use unless configured otherwise.
+pub const DEFAULT_MAX_FRACTION: f64 = 0.8;
+
+/// Per-allocation overhead of the allocator, e.g. glibc's chunk header.
+const ALLOCATION_OVERHEAD: usize = 16;
+
+/// Memory available for new allocations without swapping, from
+/// `/proc/meminfo`.
+pub fn available_bytes() -> io::Result<usize> {
+    let meminfo = fs::read_to_string("/proc/meminfo")?;
+    meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemAvailable:"))
+        .and_then(|kb| {
+            kb.trim()
+                .trim_end_matches("kB")
+                .trim()
+                .parse::<usize>()
+                .ok()
+        })
+        .map(|kb| kb * 1024)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "no MemAvailable in /proc/meminfo",
+            )
+        })
+}
+
+/// Estimated memory held by the container of the case `ds_name` over an input
+/// of `input_size_bytes` in `elements` elements.
+///
+/// Contiguous containers hold just the input. The others add:
+/// - `LinkedList`: a node per element with two pointers and an allocation.
+/// - `BTreeSet`: leaves about two thirds full after random inserts, and the
+///   edges of the internal nodes.
+/// - `HashSet`: up to twice the buckets at the maximum load factor of 7/8,
+///   and a control byte per bucket. `IndexSet` keeps the elements in a `Vec`
+///   with their hashes, and indices into it in the table.
+/// - `im::Vector`: partly filled chunks and their reference counts.
+pub fn container_bytes(ds_name: &str, input_size_bytes: usize, elements: usize) -> usize {
+    let container = ds_name.split(" (").next().unwrap_or(ds_name);
+    let per_element = |bytes: usize| elements.saturating_mul(bytes);
+
+    let overhead = if container.starts_with("LinkedList") {
+        per_element(2 * std::mem::size_of::<usize>() + ALLOCATION_OVERHEAD)
+    } else if container.starts_with("BTreeSet") || container.starts_with("BTreeMap") {
+        input_size_bytes / 2 + per_element(std::mem::size_of::<usize>())
+    } else if container.starts_with("IndexSet") || container.starts_with("IndexMap") {
+        per_element(std::mem::size_of::<u64>() + 2 * (std::mem::size_of::<usize>() + 1))
+    } else if container.contains("HashSet") || container.contains("HashMap") {
+        input_size_bytes + per_element(2)
+    } else if container.starts_with("im::") {
+        input_size_bytes / 4
+    } else {
+        0
+    };
+
+    input_size_bytes.saturating_add(overhead)
+}
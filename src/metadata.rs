@@ -0,0 +1,46 @@
+//! Metadata recorded alongside criterion's results.
+//!
+//! Criterion only stores the measurements, so anything else needed to
+//! interpret a run (machine state, configuration, per-benchmark checks) is
+//! appended to `target/criterion/metadata.tsv` as tab-separated
+//! `timestamp, scope, key, value` rows. The scope is either `run` or a
+//! benchmark ID.
+
+use crate::results;
+use std::{
+    fmt,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Scope for metadata that applies to the whole run rather than a single
+/// benchmark.
+pub const RUN: &str = "run";
+
+/// Path of the metadata file, next to criterion's own output.
+pub fn path() -> PathBuf {
+    results::criterion_dir().join("metadata.tsv")
+}
+
+/// Append a `key = value` row for `scope` to the metadata file.
+///
+/// Failing to write metadata is reported but doesn't abort the run.
+pub fn record(scope: &str, key: &str, value: impl fmt::Display) {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|t| t.as_secs())
+        .unwrap_or(0);
+    let row = format!("{}\t{}\t{}\t{}\n", timestamp, scope, key, value);
+
+    let path = path();
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| OpenOptions::new().create(true).append(true).open(&path))
+        .and_then(|mut file| file.write_all(row.as_bytes()));
+    if let Err(e) = result {
+        eprintln!("could not record metadata to {}: {}", path.display(), e);
+    }
+}
@@ -0,0 +1,108 @@
+//! Input data in a memory-mapped file.
+//!
+//! Every page of the mapping is backed by the page cache, so the first touch
+//! of a page costs a page fault, and a disk read if the page was evicted. The
+//! file is a plain array of native-endian `f64`s.
+
+use memmap2::Mmap;
+use std::{
+    fs,
+    io::{self, Write},
+    mem,
+    path::{Path, PathBuf},
+    slice,
+};
+
+/// Directory of the data files, under the target directory.
+fn data_dir() -> PathBuf {
+    crate::results::target_dir().join("sum-of-squares-data")
+}
+
+/// Path of the data file for an input of `input_size_bytes`, under the target
+/// directory.
+pub fn data_path(input_size_bytes: usize) -> PathBuf {
+    data_dir().join(format!("{}.f64", input_size_bytes))
+}
+
+/// Remove the data files of every input size, if any were written.
+pub fn remove_data_files() -> io::Result<()> {
+    match fs::remove_dir_all(data_dir()) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+/// Write `values` to `path` as native-endian `f64`s.
+///
+/// The file is synced to disk, as dirty pages can't be evicted from the page
+/// cache.
+pub fn write_data_file(path: &Path, values: &[f64]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = io::BufWriter::new(fs::File::create(path)?);
+    for value in values {
+        file.write_all(&value.to_ne_bytes())?;
+    }
+    file.flush()?;
+
+    file.get_ref().sync_all()
+}
+
+/// A read-only mapping of a data file.
+pub struct MappedData {
+    mmap: Mmap,
+}
+
+impl MappedData {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = fs::File::open(path)?;
+        // SAFETY: the data files are only written by `write_data_file`, before
+        // they are mapped
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        Ok(MappedData { mmap })
+    }
+
+    /// The mapped values. `FloatOrd` has no guaranteed layout, so the file is
+    /// viewed as plain `f64`s.
+    pub fn as_slice(&self) -> &[f64] {
+        let bytes = &self.mmap[..];
+        // Mappings are page-aligned
+        assert_eq!(bytes.as_ptr() as usize % mem::align_of::<f64>(), 0);
+
+        // SAFETY: the pointer is aligned, the length is rounded down to whole
+        // values and every bit pattern is a valid f64
+        unsafe {
+            slice::from_raw_parts(
+                bytes.as_ptr() as *const f64,
+                bytes.len() / mem::size_of::<f64>(),
+            )
+        }
+    }
+}
+
+/// Drop the pages of the file at `path` from the page cache, so that the next
+/// access reads them from disk. Pages that are mapped at the time stay cached.
+#[cfg(target_os = "linux")]
+pub fn evict_from_page_cache(path: &Path) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    let file = fs::File::open(path)?;
+    // SAFETY: the file descriptor is open for the duration of the call
+    let ret = unsafe { libc::posix_fadvise(file.as_raw_fd(), 0, 0, libc::POSIX_FADV_DONTNEED) };
+    if ret != 0 {
+        return Err(io::Error::from_raw_os_error(ret));
+    }
+
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn evict_from_page_cache(_path: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "evicting files from the page cache is only supported on Linux",
+    ))
+}
@@ -0,0 +1,94 @@
+//! Multi-threaded reductions with rayon.
+//!
+//! The same reduction is written in the two styles rayon users are likely to
+//! copy: the one-liner `map(..).sum()`, and an explicit `fold(..).reduce(..)`
+//! with a minimum chunk length per task.
+
+use crate::{affinity, Element};
+use rayon::{prelude::*, ThreadPool, ThreadPoolBuilder};
+use std::{iter, thread};
+
+/// Sum the square of each value in parallel using `par_iter().map(..).sum()`,
+/// leaving the splitting entirely to rayon.
+pub fn sum_of_squares_par_map_sum<V>(data: &[V]) -> f64
+where
+    V: Element<f64> + Sync,
+{
+    data.par_iter().map(|x| x.inner().powi(2)).sum::<f64>()
+}
+
+/// Sum the square of each value in parallel using an explicit
+/// `fold(..).reduce(..)`.
+///
+/// Rayon does not split the input into tasks shorter than `chunk_len`
+/// elements, so each task folds at least that many values sequentially before
+/// the partial sums are reduced.
+pub fn sum_of_squares_par_fold_reduce<V>(data: &[V], chunk_len: usize) -> f64
+where
+    V: Element<f64> + Sync,
+{
+    data.par_iter()
+        .with_min_len(chunk_len)
+        .fold(|| 0f64, |sum, x| sum + x.inner().powi(2))
+        .reduce(|| 0f64, |a, b| a + b)
+}
+
+/// Sum the square of each value in parallel, referencing the data-structure
+/// immutably.
+///
+/// The parallel counterpart of [`sum_of_squares_by_ref`], for any collection
+/// rayon can iterate over by reference.
+///
+/// [`sum_of_squares_by_ref`]: crate::sum_of_squares_by_ref
+pub fn sum_of_squares_par<V, T>(collection: &T) -> f64
+where
+    V: Element<f64> + Sync,
+    T: ?Sized,
+    for<'a> T: IntoParallelRefIterator<'a, Item = &'a V>,
+{
+    collection
+        .par_iter()
+        .map(|x| x.inner().powi(2))
+        .sum::<f64>()
+}
+
+/// Thread counts to sweep: powers of two up to `max_threads`, and
+/// `max_threads` itself.
+pub fn thread_counts(max_threads: usize) -> Vec<usize> {
+    let mut counts = iter::successors(Some(1), |n| Some(n * 2))
+        .take_while(|&n| n < max_threads)
+        .collect::<Vec<_>>();
+    counts.push(max_threads);
+
+    counts
+}
+
+/// A rayon thread pool builder whose worker threads unpin themselves from the
+/// core the benchmark thread may be pinned to.
+///
+/// Threads inherit the affinity of the thread that spawns them, so without
+/// this every worker would share the pinned core.
+pub fn unpinned_pool_builder() -> ThreadPoolBuilder {
+    ThreadPoolBuilder::new().start_handler(|_| {
+        if let Err(e) = affinity::unpin_current_thread() {
+            eprintln!("warning: could not unpin rayon worker thread: {}", e);
+        }
+    })
+}
+
+/// Build a thread pool of `threads` unpinned worker threads.
+pub fn thread_pool(threads: usize) -> ThreadPool {
+    unpinned_pool_builder()
+        .num_threads(threads)
+        .build()
+        .expect("could not build thread pool")
+}
+
+/// Number of threads to scale up to: one per logical CPU, or one per allowed
+/// core when threads are restricted to a subset of the cores.
+pub fn max_threads() -> usize {
+    match affinity::allowed_cores() {
+        Some(cores) => cores.len().max(1),
+        None => thread::available_parallelism().map_or(1, |n| n.get()),
+    }
+}
@@ -0,0 +1,136 @@
+//! Criterion measurements backed by hardware performance counters.
+//!
+//! Counters are opened with `perf_event_open`, so only Linux is supported, and
+//! `kernel.perf_event_paranoid` may have to be lowered for unprivileged users.
+//! Only the calling thread is counted: the parallel benchmarks report the counts
+//! of the benchmark thread alone.
+
+use crate::measurement::CountFormatter;
+use criterion::measurement::{Measurement, ValueFormatter};
+use perf_event::{
+    events::{Cache, CacheOp, CacheResult, Event, Hardware, Software, WhichCache},
+    Builder, Counter,
+};
+use std::{cell::RefCell, io};
+
+/// A criterion measurement counting a single perf event instead of wall time.
+pub struct PerfCounter {
+    counter: RefCell<Counter>,
+    formatter: CountFormatter,
+}
+
+impl PerfCounter {
+    fn new(event: impl Into<Event>, formatter: CountFormatter) -> io::Result<Self> {
+        let mut counter = Builder::new().kind(event).build()?;
+        counter.enable()?;
+
+        Ok(PerfCounter {
+            counter: RefCell::new(counter),
+            formatter,
+        })
+    }
+
+    /// Count last-level cache misses.
+    pub fn cache_misses() -> io::Result<Self> {
+        Self::new(
+            Hardware::CACHE_MISSES,
+            CountFormatter {
+                unit: "misses",
+                per_byte: "misses/B",
+                per_element: "misses/elem",
+            },
+        )
+    }
+
+    /// Count retired instructions.
+    pub fn instructions() -> io::Result<Self> {
+        Self::new(
+            Hardware::INSTRUCTIONS,
+            CountFormatter {
+                unit: "instructions",
+                per_byte: "instructions/B",
+                per_element: "instructions/elem",
+            },
+        )
+    }
+
+    /// Count mispredicted branches.
+    pub fn branch_misses() -> io::Result<Self> {
+        Self::new(
+            Hardware::BRANCH_MISSES,
+            CountFormatter {
+                unit: "mispredicts",
+                per_byte: "mispredicts/B",
+                per_element: "mispredicts/elem",
+            },
+        )
+    }
+
+    /// Count page faults, minor and major. The faults of first touching a fresh
+    /// input happen in its setup and are not counted.
+    pub fn page_faults() -> io::Result<Self> {
+        Self::new(
+            Software::PAGE_FAULTS,
+            CountFormatter {
+                unit: "faults",
+                per_byte: "faults/B",
+                per_element: "faults/elem",
+            },
+        )
+    }
+
+    /// Count data TLB misses on loads.
+    pub fn dtlb_misses() -> io::Result<Self> {
+        Self::new(
+            Cache {
+                which: WhichCache::DTLB,
+                operation: CacheOp::READ,
+                result: CacheResult::MISS,
+            },
+            CountFormatter {
+                unit: "misses",
+                per_byte: "misses/B",
+                per_element: "misses/elem",
+            },
+        )
+    }
+
+    fn read(&self) -> u64 {
+        self.counter
+            .borrow_mut()
+            .read()
+            .expect("could not read the performance counter")
+    }
+}
+
+impl Measurement for PerfCounter {
+    type Intermediate = u64;
+    type Value = u64;
+
+    fn start(&self) -> Self::Intermediate {
+        self.read()
+    }
+
+    fn end(&self, start: Self::Intermediate) -> Self::Value {
+        // A counter reset between the two reads reads less at the end. That
+        // iteration counts as no events rather than panicking, or wrapping
+        // around to a huge count.
+        self.read().saturating_sub(start)
+    }
+
+    fn add(&self, v1: &Self::Value, v2: &Self::Value) -> Self::Value {
+        v1 + v2
+    }
+
+    fn zero(&self) -> Self::Value {
+        0
+    }
+
+    fn to_f64(&self, value: &Self::Value) -> f64 {
+        *value as f64
+    }
+
+    fn formatter(&self) -> &dyn ValueFormatter {
+        &self.formatter
+    }
+}
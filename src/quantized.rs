@@ -0,0 +1,88 @@
+//! Values stored quantized to fixed-width integers and decoded on the fly.
+//!
+//! Each value is stored as `offset + q * scale` with an unsigned integer `q`,
+//! which halves or quarters the memory traffic of `f64`s at the cost of a
+//! conversion and a multiply-add per value.
+
+/// An unsigned integer that quantized values are stored as.
+pub trait Quantum: Copy {
+    /// The largest value, as `f64`.
+    const MAX: f64;
+
+    /// Round `x`, in `0..=MAX`, to the nearest quantum.
+    fn from_f64(x: f64) -> Self;
+
+    fn to_f64(self) -> f64;
+}
+
+impl Quantum for u16 {
+    const MAX: f64 = u16::MAX as f64;
+
+    fn from_f64(x: f64) -> Self {
+        x.round() as u16
+    }
+
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+}
+
+impl Quantum for u32 {
+    const MAX: f64 = u32::MAX as f64;
+
+    fn from_f64(x: f64) -> Self {
+        x.round() as u32
+    }
+
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+}
+
+/// Values quantized to `Q`, spread evenly over the range of the input.
+#[derive(Clone, Debug)]
+pub struct Quantized<Q> {
+    offset: f64,
+    scale: f64,
+    values: Vec<Q>,
+}
+
+impl<Q: Quantum> Quantized<Q> {
+    /// Quantize `values` over their range from the minimum to the maximum.
+    pub fn new(values: &[f64]) -> Self {
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let offset = if values.is_empty() { 0.0 } else { min };
+        // All-equal values quantize to zero at any scale
+        let scale = if max > min { (max - min) / Q::MAX } else { 1.0 };
+
+        Quantized {
+            offset,
+            scale,
+            values: values
+                .iter()
+                .map(|&x| Q::from_f64((x - offset) / scale))
+                .collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// The decoded values.
+    pub fn iter(&self) -> impl Iterator<Item = f64> + '_ {
+        self.values
+            .iter()
+            .map(move |&q| self.offset + q.to_f64() * self.scale)
+    }
+}
+
+/// Sum the square of each quantized value, decoding it on the fly.
+pub fn sum_of_squares_quantized<Q: Quantum>(data: &Quantized<Q>) -> f64 {
+    data.iter().map(|x| x.powi(2)).sum::<f64>()
+}
@@ -0,0 +1,162 @@
+//! A criterion measurement reading package energy from Intel RAPL.
+//!
+//! The energy counters are read through the powercap interface in sysfs, which
+//! is only readable by root on most kernels. The counters cover whole packages,
+//! so anything else running on the machine is measured as well, and they are
+//! only updated about once per millisecond: short benchmarks need long enough
+//! batches to register.
+
+use criterion::{
+    measurement::{Measurement, ValueFormatter},
+    Throughput,
+};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+const POWERCAP_DIR: &str = "/sys/class/powercap";
+
+/// Measures the energy consumed by all packages, in microjoules, instead of
+/// wall time.
+pub struct RaplEnergy {
+    domains: Vec<Domain>,
+}
+
+/// A package-level RAPL domain.
+struct Domain {
+    energy_path: PathBuf,
+    /// The counter wraps around to zero after this value.
+    max_energy_uj: u64,
+}
+
+impl RaplEnergy {
+    /// Find the package domains, eg. `intel-rapl:0`, and check that their
+    /// counters can be read.
+    pub fn new() -> io::Result<Self> {
+        let mut domains = vec![];
+        for entry in fs::read_dir(POWERCAP_DIR)? {
+            let path = entry?.path();
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            // Subdomains like `intel-rapl:0:0` are included in their package
+            if !name.starts_with("intel-rapl:") || name.matches(':').count() != 1 {
+                continue;
+            }
+
+            let energy_path = path.join("energy_uj");
+            read_uj(&energy_path)?;
+            domains.push(Domain {
+                energy_path,
+                max_energy_uj: read_uj(&path.join("max_energy_range_uj"))?,
+            });
+        }
+
+        if domains.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "no RAPL package domains found",
+            ));
+        }
+
+        Ok(RaplEnergy { domains })
+    }
+
+    fn read(&self) -> Vec<u64> {
+        self.domains
+            .iter()
+            .map(|domain| {
+                read_uj(&domain.energy_path).expect("could not read the RAPL energy counter")
+            })
+            .collect()
+    }
+}
+
+fn read_uj(path: &Path) -> io::Result<u64> {
+    fs::read_to_string(path)?
+        .trim()
+        .parse()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+impl Measurement for RaplEnergy {
+    type Intermediate = Vec<u64>;
+    type Value = u64;
+
+    fn start(&self) -> Self::Intermediate {
+        self.read()
+    }
+
+    fn end(&self, start: Self::Intermediate) -> Self::Value {
+        self.read()
+            .into_iter()
+            .zip(start)
+            .zip(&self.domains)
+            .map(|((end, start), domain)| {
+                if end >= start {
+                    end - start
+                } else {
+                    // The counter wrapped around during the measurement
+                    domain.max_energy_uj - start + end
+                }
+            })
+            .sum()
+    }
+
+    fn add(&self, v1: &Self::Value, v2: &Self::Value) -> Self::Value {
+        v1 + v2
+    }
+
+    fn zero(&self) -> Self::Value {
+        0
+    }
+
+    fn to_f64(&self, value: &Self::Value) -> f64 {
+        *value as f64
+    }
+
+    fn formatter(&self) -> &dyn ValueFormatter {
+        &EnergyFormatter
+    }
+}
+
+/// Formats microjoules in µJ, mJ or J, and throughputs as nanojoules per byte
+/// or per element.
+struct EnergyFormatter;
+
+impl ValueFormatter for EnergyFormatter {
+    fn scale_values(&self, typical_value: f64, values: &mut [f64]) -> &'static str {
+        let (factor, unit) = if typical_value < 1e3 {
+            (1.0, "µJ")
+        } else if typical_value < 1e6 {
+            (1e-3, "mJ")
+        } else {
+            (1e-6, "J")
+        };
+        for value in values {
+            *value *= factor;
+        }
+
+        unit
+    }
+
+    fn scale_throughputs(
+        &self,
+        _typical_value: f64,
+        throughput: &Throughput,
+        values: &mut [f64],
+    ) -> &'static str {
+        let (n, unit) = match *throughput {
+            Throughput::Bytes(n) => (n, "nJ/B"),
+            Throughput::Elements(n) => (n, "nJ/elem"),
+        };
+        for value in values {
+            *value *= 1e3 / n as f64;
+        }
+
+        unit
+    }
+
+    fn scale_for_machines(&self, _values: &mut [f64]) -> &'static str {
+        "µJ"
+    }
+}
@@ -0,0 +1,202 @@
+//! Replicating cases over fresh allocations at different addresses.
+//!
+//! Where an input lands decides which cache sets and pages it maps to, so two
+//! allocations of the same container can differ in speed by more than two
+//! containers do. Each replica of a case allocates its input anew behind a
+//! displacement block of random size, and the spread of the replicas' means is
+//! reported apart from the spread of the samples within each replica.
+
+use crate::{aligned::CACHE_LINE, results::Estimate};
+use criterion::black_box;
+use rand::Rng;
+
+/// Displacement blocks are up to 16 pages, so that the inputs start at
+/// different cache sets and page offsets.
+pub const MAX_DISPLACEMENT_BYTES: usize = 64 * 1024;
+
+/// Name of replica `replica` of the case `ds_name`, e.g.
+/// `Vec (by reference) [alloc 2]`.
+pub fn name(ds_name: &str, replica: usize) -> String {
+    format!("{} [alloc {}]", ds_name, replica)
+}
+
+/// Split a benchmark ID of a replica into the ID of the case and the replica
+/// number.
+pub fn split_id(full_id: &str) -> Option<(String, usize)> {
+    let start = full_id.find(" [alloc ")?;
+    let end = start + full_id[start..].find(']')?;
+    let replica = full_id[start + " [alloc ".len()..end].parse().ok()?;
+
+    Some((
+        format!("{}{}", &full_id[..start], &full_id[end + 1..]),
+        replica,
+    ))
+}
+
+/// A heap block held while a replica allocates its input, so that the input
+/// starts at a different address than that of the previous replica.
+pub struct Displacement {
+    _block: Vec<u8>,
+}
+
+impl Displacement {
+    /// Allocate a block of a random number of cache lines and touch it, so
+    /// that it can't be elided.
+    pub fn new(rng: &mut impl Rng) -> Self {
+        let lines = rng.gen_range(0..=MAX_DISPLACEMENT_BYTES / CACHE_LINE);
+        let block = vec![1u8; lines * CACHE_LINE];
+        black_box(block.as_ptr());
+
+        Displacement { _block: block }
+    }
+}
+
+/// Variance of a replicated case, in ns².
+#[derive(Clone, Debug)]
+pub struct Variance {
+    /// Benchmark ID of the case without the replica number.
+    pub full_id: String,
+    pub replicas: usize,
+    /// Mean time per iteration over the replicas in nanoseconds.
+    pub mean_ns: f64,
+    /// Sample variance of the means of the replicas.
+    pub between: f64,
+    /// Mean of the variances within each replica.
+    pub within: f64,
+}
+
+impl Variance {
+    /// Share of the total variance that comes from the allocation.
+    pub fn between_fraction(&self) -> f64 {
+        self.between / (self.between + self.within)
+    }
+}
+
+/// Variances of every case measured in at least two replicas, sorted by ID.
+/// Replicas without a standard deviation are left out of the within variance.
+pub fn variances(estimates: &[Estimate]) -> Vec<Variance> {
+    let mut replicas = estimates
+        .iter()
+        .filter_map(|estimate| split_id(&estimate.full_id).map(|(id, _)| (id, estimate)))
+        .collect::<Vec<_>>();
+    replicas.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut variances = Vec::new();
+    for group in replicas.chunk_by(|a, b| a.0 == b.0) {
+        let n = group.len();
+        if n < 2 {
+            continue;
+        }
+
+        let mean_ns = group.iter().map(|(_, e)| e.mean_ns).sum::<f64>() / n as f64;
+        let between = group
+            .iter()
+            .map(|(_, e)| (e.mean_ns - mean_ns).powi(2))
+            .sum::<f64>()
+            / (n - 1) as f64;
+        let within = group
+            .iter()
+            .filter_map(|(_, e)| e.std_dev_ns)
+            .map(|std_dev| std_dev.powi(2))
+            .collect::<Vec<_>>();
+        let within = within.iter().sum::<f64>() / within.len().max(1) as f64;
+
+        variances.push(Variance {
+            full_id: group[0].0.clone(),
+            replicas: n,
+            mean_ns,
+            between,
+            within,
+        });
+    }
+
+    variances
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn estimate(full_id: &str, mean_ns: f64, std_dev_ns: Option<f64>) -> Estimate {
+        Estimate {
+            full_id: full_id.to_string(),
+            mean_ns,
+            std_dev_ns,
+            throughput_bytes: None,
+            throughput_elements: None,
+        }
+    }
+
+    #[test]
+    fn splits_the_replica_number_from_the_id() {
+        let cases = [
+            (
+                "Sum of squares/Vec (by reference) [alloc 2]/1 kB",
+                ("Sum of squares/Vec (by reference)/1 kB", 2),
+            ),
+            (
+                "Sum of squares/Vec [cold] [alloc 10]/4 MB",
+                ("Sum of squares/Vec [cold]/4 MB", 10),
+            ),
+            ("Clone/Vec (clone) [alloc 1]", ("Clone/Vec (clone)", 1)),
+        ];
+        for (full_id, (id, replica)) in cases {
+            assert_eq!(split_id(full_id), Some((id.to_string(), replica)));
+        }
+        assert_eq!(
+            split_id(&format!("Group/{}/1 kB", name("Vec", 3))),
+            Some(("Group/Vec/1 kB".to_string(), 3))
+        );
+    }
+
+    #[test]
+    fn rejects_ids_without_a_replica_number() {
+        for full_id in [
+            "Sum of squares/Vec (by reference)/1 kB",
+            "Sum of squares/Vec [cold]/1 kB",
+            "Sum of squares/Vec [alloc]/1 kB",
+            "Sum of squares/Vec [alloc x]/1 kB",
+            "Sum of squares/Vec [alloc 2/1 kB",
+        ] {
+            assert_eq!(split_id(full_id), None, "{:?}", full_id);
+        }
+    }
+
+    #[test]
+    fn separates_the_variance_between_and_within_replicas() {
+        let estimates = [
+            estimate("G/Vec [alloc 1]/1 kB", 10.0, Some(1.0)),
+            estimate("G/Vec [alloc 2]/1 kB", 12.0, Some(2.0)),
+            estimate("G/Vec [alloc 3]/1 kB", 14.0, Some(3.0)),
+        ];
+        let variances = variances(&estimates);
+
+        assert_eq!(variances.len(), 1);
+        let variance = &variances[0];
+        assert_eq!(variance.full_id, "G/Vec/1 kB");
+        assert_eq!(variance.replicas, 3);
+        assert_eq!(variance.mean_ns, 12.0);
+        // ((-2)² + 0² + 2²) / (3 - 1)
+        assert_eq!(variance.between, 4.0);
+        // (1² + 2² + 3²) / 3
+        assert!((variance.within - 14.0 / 3.0).abs() < 1e-12);
+        assert!((variance.between_fraction() - 4.0 / (4.0 + 14.0 / 3.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn leaves_out_cases_of_a_single_replica_and_unreplicated_ones() {
+        let estimates = [
+            estimate("G/Vec [alloc 1]/1 kB", 10.0, Some(1.0)),
+            estimate("G/Vec [alloc 1]/1 MB", 10.0, Some(1.0)),
+            estimate("G/Vec [alloc 2]/1 MB", 20.0, None),
+            estimate("G/VecDeque/1 MB", 10.0, Some(1.0)),
+        ];
+        let variances = variances(&estimates);
+
+        assert_eq!(variances.len(), 1);
+        assert_eq!(variances[0].full_id, "G/Vec/1 MB");
+        assert_eq!(variances[0].between, 50.0);
+        // Only the replica with a standard deviation counts
+        assert_eq!(variances[0].within, 1.0);
+    }
+}
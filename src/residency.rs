@@ -0,0 +1,70 @@
+//! Checking that input data is resident in physical memory.
+//!
+//! Pages that are swapped out or not yet faulted in make the first iterations
+//! over a large input measure the kernel's page fault handler instead of the
+//! data-structure.
+
+use std::collections::BTreeSet;
+
+/// Fraction of the memory pages holding `elements` that are resident in RAM.
+///
+/// Every element's address is mapped to its page, so this works for node-based
+/// containers as well as contiguous ones. Returns `None` where residency can't
+/// be queried.
+#[cfg(target_os = "linux")]
+pub fn resident_fraction<'a, V, I>(elements: I) -> Option<f64>
+where
+    V: 'a,
+    I: IntoIterator<Item = &'a V>,
+{
+    // SAFETY: sysconf has no preconditions
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+    let pages = elements
+        .into_iter()
+        .map(|x| x as *const V as usize / page_size)
+        .collect::<BTreeSet<_>>();
+    if pages.is_empty() {
+        return Some(1.0);
+    }
+
+    // Query each run of consecutive pages with a single call
+    let mut resident = 0;
+    let mut status = Vec::new();
+    let mut pages_iter = pages.iter().copied().peekable();
+    while let Some(first) = pages_iter.next() {
+        let mut run_len = 1;
+        while pages_iter.peek() == Some(&(first + run_len)) {
+            pages_iter.next();
+            run_len += 1;
+        }
+
+        status.resize(run_len, 0u8);
+        // SAFETY: the range only covers pages that hold live elements, and
+        // `status` has one byte per page
+        let ret = unsafe {
+            libc::mincore(
+                (first * page_size) as *mut libc::c_void,
+                run_len * page_size,
+                status.as_mut_ptr(),
+            )
+        };
+        if ret != 0 {
+            return None;
+        }
+        resident += status.iter().filter(|&&s| s & 1 != 0).count();
+    }
+
+    Some(resident as f64 / pages.len() as f64)
+}
+
+/// Fraction of the memory pages holding `elements` that are resident in RAM.
+///
+/// Residency can only be queried on Linux, so this always returns `None`.
+#[cfg(not(target_os = "linux"))]
+pub fn resident_fraction<'a, V, I>(_elements: I) -> Option<f64>
+where
+    V: 'a,
+    I: IntoIterator<Item = &'a V>,
+{
+    None
+}
@@ -0,0 +1,178 @@
+//! Reading and writing benchmark results.
+//!
+//! Results live in namespaces under criterion's output directory: `measured`
+//! results are criterion's own wall-clock estimates, `simulated` results are
+//! cache simulator counts for the same benchmark IDs, written by the
+//! `simulate` subcommand of the binary, and `counted` results are criterion's
+//! estimates of the other measurements of `SOS_MEASUREMENT`, such as cache
+//! misses, in a directory of each measurement.
+
+use std::{
+    env, fs,
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+};
+
+/// Namespace of criterion's wall-clock measurements.
+pub const MEASURED: &str = "measured";
+/// Namespace of the cache simulator counts.
+pub const SIMULATED: &str = "simulated";
+/// Namespace of the measurements other than wall time.
+pub const COUNTED: &str = "counted";
+/// The measurement criterion takes by default, in nanoseconds.
+pub const WALL_TIME: &str = "wall-time";
+
+/// Cargo's target directory, `target` unless overridden.
+pub fn target_dir() -> PathBuf {
+    env::var_os("CARGO_TARGET_DIR")
+        .unwrap_or_else(|| "target".into())
+        .into()
+}
+
+/// Criterion's output directory, `target/criterion` unless the target
+/// directory is overridden.
+pub fn criterion_dir() -> PathBuf {
+    target_dir().join("criterion")
+}
+
+/// Criterion's output directory for the measurement called `measurement`,
+/// so that estimates in other units than nanoseconds aren't read as wall
+/// time.
+pub fn measurement_dir(measurement: &str) -> PathBuf {
+    if measurement == WALL_TIME {
+        criterion_dir()
+    } else {
+        criterion_dir().join(COUNTED).join(measurement)
+    }
+}
+
+/// Criterion's estimate for a single benchmark.
+#[derive(Clone, Debug)]
+pub struct Estimate {
+    /// Benchmark ID as `group/function/parameter`.
+    pub full_id: String,
+    /// Mean time per iteration in nanoseconds.
+    pub mean_ns: f64,
+    /// Standard deviation of the time per iteration over the samples in
+    /// nanoseconds.
+    pub std_dev_ns: Option<f64>,
+    /// Input size in bytes, if the benchmark reported its throughput in bytes.
+    pub throughput_bytes: Option<u64>,
+    /// Input length, if the benchmark reported its throughput in elements.
+    pub throughput_elements: Option<u64>,
+}
+
+/// Read the latest wall-time estimate of every benchmark criterion has
+/// results for.
+pub fn read_measured() -> io::Result<Vec<Estimate>> {
+    let mut estimates = Vec::new();
+    read_measured_in(&criterion_dir(), &mut estimates)?;
+    estimates.sort_by(|a, b| a.full_id.cmp(&b.full_id));
+
+    Ok(estimates)
+}
+
+fn read_measured_in(dir: &Path, estimates: &mut Vec<Estimate>) -> io::Result<()> {
+    let latest = dir.join("new");
+    if latest.join("benchmark.json").is_file() {
+        if let Some(estimate) = read_estimate(&latest)? {
+            estimates.push(estimate);
+        }
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir()
+            && !path.ends_with(SIMULATED)
+            && !path.ends_with(COUNTED)
+            && !path.ends_with("report")
+        {
+            read_measured_in(&path, estimates)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Read one of criterion's `new` directories.
+fn read_estimate(dir: &Path) -> io::Result<Option<Estimate>> {
+    let benchmark = read_json(&dir.join("benchmark.json"))?;
+    let estimates = read_json(&dir.join("estimates.json"))?;
+
+    let full_id = benchmark["full_id"].as_str().map(String::from);
+    let mean_ns = estimates["mean"]["point_estimate"].as_f64();
+    let std_dev_ns = estimates["std_dev"]["point_estimate"].as_f64();
+    let throughput_bytes = benchmark["throughput"]["Bytes"].as_u64();
+    let throughput_elements = benchmark["throughput"]["Elements"].as_u64();
+
+    Ok(full_id.zip(mean_ns).map(|(full_id, mean_ns)| Estimate {
+        full_id,
+        mean_ns,
+        std_dev_ns,
+        throughput_bytes,
+        throughput_elements,
+    }))
+}
+
+fn read_json(path: &Path) -> io::Result<serde_json::Value> {
+    let file = fs::File::open(path)?;
+    serde_json::from_reader(BufReader::new(file))
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// A single simulated count for a benchmark, e.g. its D1 misses.
+#[derive(Clone, Debug)]
+pub struct SimulatedCount {
+    /// Benchmark ID as `group/function/parameter`, matching the measured ID.
+    pub full_id: String,
+    pub metric: String,
+    pub value: u64,
+}
+
+/// Path of the simulated counts, as tab-separated `full_id, metric, value`
+/// rows.
+pub fn simulated_path() -> PathBuf {
+    criterion_dir().join(SIMULATED).join("counts.tsv")
+}
+
+/// Replace the simulated counts with `counts`.
+pub fn write_simulated(counts: &[SimulatedCount]) -> io::Result<()> {
+    let path = simulated_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = io::BufWriter::new(fs::File::create(path)?);
+    for count in counts {
+        writeln!(file, "{}\t{}\t{}", count.full_id, count.metric, count.value)?;
+    }
+
+    file.flush()
+}
+
+/// Read all simulated counts, or none if the simulation hasn't been run.
+pub fn read_simulated() -> io::Result<Vec<SimulatedCount>> {
+    let file = match fs::File::open(simulated_path()) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut counts = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        let mut fields = line.split('\t');
+        if let (Some(full_id), Some(metric), Some(Ok(value))) =
+            (fields.next(), fields.next(), fields.next().map(str::parse))
+        {
+            counts.push(SimulatedCount {
+                full_id: full_id.to_string(),
+                metric: metric.to_string(),
+                value,
+            });
+        }
+    }
+
+    Ok(counts)
+}
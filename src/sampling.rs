@@ -0,0 +1,80 @@
+//! Criterion's sampling settings scaled with the input size.
+//!
+//! Inputs that fit in the caches take microseconds per iteration, so they can
+//! afford many samples. Inputs beyond the last-level cache take milliseconds,
+//! and criterion's linear sampling, which runs 1 + 2 + … + n iterations over
+//! the n samples, spends minutes on each of them: they get fewer samples of a
+//! fixed iteration count and a longer measurement window instead.
+
+use crate::schedule;
+use std::time::Duration;
+
+/// Inputs from this size up get more samples than criterion's default.
+const SMALL_BYTES: usize = 1024 * 1024;
+/// Inputs from this size up are measured with flat sampling.
+const LARGE_BYTES: usize = 16 * 1024 * 1024;
+
+/// How criterion samples a single case.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sampling {
+    pub sample_size: u64,
+    pub measurement_time: Duration,
+    pub warm_up_time: Duration,
+    /// Flat sampling runs the same number of iterations in every sample,
+    /// linear sampling `i` times a base count in sample `i`.
+    pub flat: bool,
+    /// Time criterion spends on the analysis and the plots of the case.
+    pub report_time: Duration,
+}
+
+impl Sampling {
+    /// Criterion's default settings with linear sampling, as used for every
+    /// case when the sampling isn't scaled.
+    pub const DEFAULT: Sampling = Sampling {
+        sample_size: schedule::SAMPLE_SIZE,
+        measurement_time: schedule::MEASUREMENT_TIME,
+        warm_up_time: schedule::WARM_UP_TIME,
+        flat: false,
+        report_time: schedule::REPORT_TIME,
+    };
+
+    /// Settings for an input of `input_size_bytes`. The warm-up time isn't
+    /// scaled.
+    pub fn for_size(input_size_bytes: usize) -> Self {
+        if input_size_bytes < SMALL_BYTES {
+            Sampling {
+                sample_size: 200,
+                measurement_time: Duration::from_secs(3),
+                warm_up_time: schedule::WARM_UP_TIME,
+                flat: false,
+                report_time: schedule::REPORT_TIME,
+            }
+        } else if input_size_bytes < LARGE_BYTES {
+            Sampling::DEFAULT
+        } else {
+            Sampling {
+                sample_size: 20,
+                measurement_time: Duration::from_secs(10),
+                warm_up_time: schedule::WARM_UP_TIME,
+                flat: true,
+                report_time: schedule::REPORT_TIME,
+            }
+        }
+    }
+
+    /// Number of iterations criterion runs to collect the samples of a case
+    /// whose iterations take `iteration_time`.
+    pub fn iterations(&self, iteration_time: Duration) -> u64 {
+        let n = self.sample_size;
+        let iteration_nanos = iteration_time.as_nanos().max(1) as f64;
+        let measurement_nanos = self.measurement_time.as_nanos() as f64;
+        if self.flat {
+            let per_sample = (measurement_nanos / (iteration_nanos * n as f64)).ceil() as u64;
+            n * per_sample.max(1)
+        } else {
+            let total_runs = n * (n + 1) / 2;
+            let d = (measurement_nanos / (iteration_nanos * total_runs as f64)).ceil() as u64;
+            d.max(1) * total_runs
+        }
+    }
+}
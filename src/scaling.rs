@@ -0,0 +1,188 @@
+//! Speedup and parallel efficiency from the thread scaling benchmarks.
+//!
+//! The thread count group names its benchmarks `<container> (<n> threads)`, so the
+//! table can be derived from criterion's estimates alone.
+
+use crate::results::{self, Estimate};
+use std::{
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+/// Name of the benchmark group the scaling table is derived from.
+pub const GROUP: &str = "Sum of squares (threads)";
+
+/// Scaling of one container at one input size and thread count.
+#[derive(Clone, Debug)]
+pub struct ScalingRow {
+    pub container: String,
+    pub input_size: String,
+    pub threads: usize,
+    pub mean_ns: f64,
+    /// Single-threaded time divided by the time with `threads` threads.
+    pub speedup: f64,
+    /// Speedup divided by the number of threads; 1.0 is perfect scaling.
+    pub efficiency: f64,
+}
+
+/// Path of the scaling table, next to criterion's own output.
+pub fn path() -> PathBuf {
+    results::criterion_dir().join("scaling.tsv")
+}
+
+/// Split a benchmark function name like `Vec (4 threads)` into the container
+/// and the thread count.
+fn parse_function_id(function: &str) -> Option<(&str, usize)> {
+    let (container, threads) = function.strip_suffix(" threads)")?.rsplit_once(" (")?;
+    Some((container, threads.parse().ok()?))
+}
+
+/// Derive the scaling table from the estimates of the thread count group.
+///
+/// Rows without a single-threaded measurement of the same container and size
+/// are left out, as they have no baseline.
+pub fn scaling_table(estimates: &[Estimate]) -> Vec<ScalingRow> {
+    let points = estimates
+        .iter()
+        .filter_map(|estimate| {
+            let mut parts = estimate.full_id.splitn(3, '/');
+            match (parts.next(), parts.next(), parts.next()) {
+                (Some(GROUP), Some(function), Some(input_size)) => {
+                    let (container, threads) = parse_function_id(function)?;
+                    Some((container, input_size, threads, estimate.mean_ns))
+                }
+                _ => None,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    points
+        .iter()
+        .filter_map(|&(container, input_size, threads, mean_ns)| {
+            let single = points
+                .iter()
+                .find(|&&(c, s, t, _)| c == container && s == input_size && t == 1)?;
+            let speedup = single.3 / mean_ns;
+
+            Some(ScalingRow {
+                container: container.to_string(),
+                input_size: input_size.to_string(),
+                threads,
+                mean_ns,
+                speedup,
+                efficiency: speedup / threads as f64,
+            })
+        })
+        .collect()
+}
+
+/// Write the scaling table as tab-separated values to [`path`].
+pub fn write_table(rows: &[ScalingRow]) -> io::Result<()> {
+    let mut file = io::BufWriter::new(fs::File::create(path())?);
+    writeln!(
+        file,
+        "container\tsize\tthreads\tmean_ns\tspeedup\tefficiency"
+    )?;
+    for row in rows {
+        writeln!(
+            file,
+            "{}\t{}\t{}\t{:.1}\t{:.3}\t{:.3}",
+            row.container, row.input_size, row.threads, row.mean_ns, row.speedup, row.efficiency
+        )?;
+    }
+
+    file.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn estimate(full_id: &str, mean_ns: f64) -> Estimate {
+        Estimate {
+            full_id: full_id.to_string(),
+            mean_ns,
+            std_dev_ns: None,
+            throughput_bytes: None,
+            throughput_elements: None,
+        }
+    }
+
+    #[test]
+    fn parses_the_container_and_thread_count() {
+        assert_eq!(parse_function_id("Vec (4 threads)"), Some(("Vec", 4)));
+        assert_eq!(
+            parse_function_id("Box<[_]> (by value) (16 threads)"),
+            Some(("Box<[_]> (by value)", 16))
+        );
+    }
+
+    #[test]
+    fn rejects_function_ids_without_a_thread_count() {
+        for function in [
+            "Vec",
+            "Vec (by value)",
+            "Vec (four threads)",
+            "Vec (-1 threads)",
+            "Vec 4 threads)",
+            "Vec (4 threads",
+        ] {
+            assert_eq!(parse_function_id(function), None, "{:?}", function);
+        }
+    }
+
+    #[test]
+    fn derives_speedup_and_efficiency_from_one_thread() {
+        let estimates = [
+            estimate("Sum of squares (threads)/Vec (1 threads)/1 MB", 400.0),
+            estimate("Sum of squares (threads)/Vec (2 threads)/1 MB", 250.0),
+            estimate("Sum of squares (threads)/Vec (4 threads)/1 MB", 100.0),
+        ];
+        let rows = scaling_table(&estimates);
+
+        let table: Vec<_> = rows
+            .iter()
+            .map(|row| (row.threads, row.speedup, row.efficiency))
+            .collect();
+        assert_eq!(table, [(1, 1.0, 1.0), (2, 1.6, 0.8), (4, 4.0, 1.0)]);
+        assert!(rows
+            .iter()
+            .all(|row| row.container == "Vec" && row.input_size == "1 MB"));
+    }
+
+    #[test]
+    fn compares_each_container_and_size_to_its_own_baseline() {
+        let estimates = [
+            estimate("Sum of squares (threads)/Vec (1 threads)/1 kB", 10.0),
+            estimate("Sum of squares (threads)/Vec (1 threads)/1 MB", 400.0),
+            estimate("Sum of squares (threads)/HashSet (1 threads)/1 MB", 800.0),
+            estimate("Sum of squares (threads)/Vec (2 threads)/1 MB", 200.0),
+            estimate("Sum of squares (threads)/HashSet (2 threads)/1 MB", 200.0),
+        ];
+        let speedups: Vec<_> = scaling_table(&estimates)
+            .into_iter()
+            .filter(|row| row.threads == 2)
+            .map(|row| (row.container, row.speedup))
+            .collect();
+
+        assert_eq!(
+            speedups,
+            [("Vec".to_string(), 2.0), ("HashSet".to_string(), 4.0)]
+        );
+    }
+
+    #[test]
+    fn leaves_out_rows_without_a_baseline_and_other_groups() {
+        let estimates = [
+            estimate("Sum of squares (threads)/Vec (2 threads)/1 MB", 200.0),
+            estimate("Sum of squares (threads)/Vec (1 threads)/1 kB", 10.0),
+            estimate("Sum of squares/Vec (by reference)/1 MB", 100.0),
+            estimate("Sum of squares (rayon)/Vec (1 threads)/1 MB", 100.0),
+        ];
+        let rows = scaling_table(&estimates);
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].input_size, "1 kB");
+    }
+}
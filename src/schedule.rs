@@ -0,0 +1,137 @@
+//! Estimating how long a benchmark campaign takes before running it.
+//!
+//! Each case is probed for a fraction of a second to measure one iteration
+//! (setup clone, routine and drop), and the time criterion would spend on it
+//! is extrapolated from criterion's own sampling schemes. When only listing the
+//! cases, the iteration time is modelled from the memory footprint instead.
+
+use crate::{human_readable_size, sampling::Sampling};
+use std::{fmt, time::Duration};
+
+/// Criterion's default warm-up time.
+pub const WARM_UP_TIME: Duration = Duration::from_secs(3);
+/// Criterion's default target measurement time.
+pub const MEASUREMENT_TIME: Duration = Duration::from_secs(5);
+/// Criterion's default number of samples.
+pub const SAMPLE_SIZE: u64 = 100;
+/// Rough time criterion spends on analysis and plots for a single benchmark,
+/// unless its sampling says otherwise.
+pub const REPORT_TIME: Duration = Duration::from_millis(1500);
+
+/// How long each case is probed for during a dry run.
+pub const PROBE_TIME: Duration = Duration::from_millis(200);
+/// Rate at which a listed case is assumed to go through its memory footprint,
+/// in bytes per nanosecond. Pointer-chasing containers are slower, but the
+/// model only needs to tell seconds from hours.
+pub const MODELLED_BYTES_PER_NS: f64 = 4.0;
+
+/// Modelled time of one iteration over `footprint_bytes`.
+pub fn modelled_iteration_time(footprint_bytes: usize) -> Duration {
+    Duration::from_nanos((footprint_bytes as f64 / MODELLED_BYTES_PER_NS) as u64)
+}
+
+/// Estimated cost of a single benchmark case.
+#[derive(Clone, Debug)]
+pub struct CaseEstimate {
+    pub id: String,
+    /// Wall time of one iteration, including the setup outside the timed
+    /// region.
+    pub iteration_time: Duration,
+    /// How criterion samples the case.
+    pub sampling: Sampling,
+    /// Memory the case holds while it runs: its input and any copies of it.
+    pub footprint_bytes: usize,
+}
+
+impl CaseEstimate {
+    /// Time criterion is expected to spend on this case, warm-up and report
+    /// generation included.
+    ///
+    /// When one iteration is slow, criterion cannot stay within the measurement
+    /// time and runs its minimum number of iterations, 1 + 2 + … + n in linear
+    /// sampling mode and n in flat sampling mode.
+    pub fn duration(&self) -> Duration {
+        let iterations = self.sampling.iterations(self.iteration_time);
+        let measurement = Duration::from_nanos(
+            (self.iteration_time.as_nanos() as u64).saturating_mul(iterations),
+        );
+
+        self.sampling.warm_up_time.max(self.iteration_time)
+            + self.sampling.measurement_time.max(measurement)
+            + self.sampling.report_time
+    }
+}
+
+/// The cases of a campaign in the order they will run.
+#[derive(Clone, Debug, Default)]
+pub struct Schedule {
+    pub cases: Vec<CaseEstimate>,
+}
+
+impl Schedule {
+    pub fn push(
+        &mut self,
+        id: String,
+        iteration_time: Duration,
+        sampling: Sampling,
+        footprint_bytes: usize,
+    ) {
+        self.cases.push(CaseEstimate {
+            id,
+            iteration_time,
+            sampling,
+            footprint_bytes,
+        });
+    }
+
+    /// Estimated duration of the whole campaign.
+    pub fn total(&self) -> Duration {
+        self.cases.iter().map(CaseEstimate::duration).sum()
+    }
+
+    /// Largest footprint of any case, as the cases run one at a time.
+    pub fn peak_footprint(&self) -> usize {
+        self.cases
+            .iter()
+            .map(|case| case.footprint_bytes)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut start = Duration::from_secs(0);
+        for case in &self.cases {
+            let duration = case.duration();
+            writeln!(
+                f,
+                "{:>10} {:>10} {:>10} {}",
+                format_duration(start),
+                format_duration(duration),
+                human_readable_size(case.footprint_bytes),
+                case.id
+            )?;
+            start += duration;
+        }
+        writeln!(
+            f,
+            "{} benchmarks, estimated total {}, peak footprint {}",
+            self.cases.len(),
+            format_duration(self.total()),
+            human_readable_size(self.peak_footprint())
+        )
+    }
+}
+
+/// Format a duration as hours, minutes and seconds, e.g. "1h02m05s".
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 60 * 60 {
+        format!("{}h{:02}m{:02}s", secs / 3600, secs / 60 % 60, secs % 60)
+    } else if secs >= 60 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}.{:01}s", secs, duration.subsec_millis() / 100)
+    }
+}
@@ -0,0 +1,56 @@
+//! Hand-vectorized kernels using portable SIMD (`std::simd`).
+//!
+//! These give an upper bound to compare the autovectorized generic kernels
+//! against. Requires a nightly compiler and the `simd` feature.
+
+use crate::Element;
+use std::collections::VecDeque;
+use std::simd::prelude::*;
+
+/// Number of f64 lanes per vector.
+pub const LANES: usize = 8;
+
+type F64s = Simd<f64, LANES>;
+
+/// Sum the square of each value in a slice, `LANES` values at a time.
+///
+/// Keeps one vector of partial sums and reduces it horizontally at the end, so
+/// the order of additions differs from the sequential kernels.
+pub fn sum_of_squares_simd<V>(data: &[V]) -> f64
+where
+    V: Element<f64>,
+{
+    let chunks = data.chunks_exact(LANES);
+    let tail = chunks
+        .remainder()
+        .iter()
+        .map(|x| x.inner().powi(2))
+        .sum::<f64>();
+
+    let sums = chunks.fold(F64s::splat(0.0), |sums, chunk| {
+        let x = F64s::from_array(std::array::from_fn(|i| chunk[i].inner()));
+        sums + x * x
+    });
+
+    sums.reduce_sum() + tail
+}
+
+/// Sum the square of each value in a slice of plain `f64`s, `LANES` values at
+/// a time.
+pub fn sum_of_squares_simd_f64(data: &[f64]) -> f64 {
+    let (head, body, tail) = data.as_simd::<LANES>();
+
+    let sums = body.iter().fold(F64s::splat(0.0), |sums, &x| sums + x * x);
+
+    sums.reduce_sum() + head.iter().chain(tail).map(|x| x.powi(2)).sum::<f64>()
+}
+
+/// Sum the square of each value in a `VecDeque` by running
+/// [`sum_of_squares_simd`] over both of its contiguous halves.
+pub fn sum_of_squares_simd_deque<V>(data: &VecDeque<V>) -> f64
+where
+    V: Element<f64>,
+{
+    let (front, back) = data.as_slices();
+    sum_of_squares_simd(front) + sum_of_squares_simd(back)
+}
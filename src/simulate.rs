@@ -0,0 +1,208 @@
+//! Running kernels under cachegrind's deterministic cache simulation.
+//!
+//! Cachegrind counts the whole process, so every matrix point is run twice:
+//! once with the kernel and once with only the data setup. The difference is
+//! attributed to the kernel.
+
+use std::{
+    io,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+/// Cache simulator counts of a single run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheCounts {
+    pub instructions: u64,
+    pub data_refs: u64,
+    pub d1_misses: u64,
+    pub lld_misses: u64,
+}
+
+impl CacheCounts {
+    /// Counts of `self` in excess of `baseline`.
+    pub fn saturating_sub(&self, baseline: &CacheCounts) -> CacheCounts {
+        CacheCounts {
+            instructions: self.instructions.saturating_sub(baseline.instructions),
+            data_refs: self.data_refs.saturating_sub(baseline.data_refs),
+            d1_misses: self.d1_misses.saturating_sub(baseline.d1_misses),
+            lld_misses: self.lld_misses.saturating_sub(baseline.lld_misses),
+        }
+    }
+
+    /// The counts by metric name, as stored in the simulated results.
+    pub fn metrics(&self) -> [(&'static str, u64); 4] {
+        [
+            ("instructions", self.instructions),
+            ("data_refs", self.data_refs),
+            ("d1_misses", self.d1_misses),
+            ("lld_misses", self.lld_misses),
+        ]
+    }
+}
+
+/// Run `program` with `args` under cachegrind and return its counts.
+pub fn run_cachegrind(program: &Path, args: &[String]) -> io::Result<CacheCounts> {
+    let output = Command::new("valgrind")
+        .arg("--tool=cachegrind")
+        .arg("--cache-sim=yes")
+        .arg("--cachegrind-out-file=/dev/null")
+        .arg(program)
+        .args(args)
+        .stdout(Stdio::null())
+        .output()?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "cachegrind failed: {}",
+            stderr.trim()
+        )));
+    }
+
+    parse_summary(&stderr).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "no cache summary in cachegrind output",
+        )
+    })
+}
+
+/// Parse cachegrind's summary, which is printed to stderr as lines like
+/// `==1234== D1  misses:   20,160  ( 15,020 rd + 5,140 wr)`.
+fn parse_summary(stderr: &str) -> Option<CacheCounts> {
+    let mut counts = CacheCounts::default();
+    let mut found = 0;
+
+    for line in stderr.lines() {
+        let line = line.splitn(3, "==").nth(2).unwrap_or(line);
+        let mut parts = line.splitn(2, ':');
+        let (key, value) = match (parts.next(), parts.next()) {
+            (Some(key), Some(value)) => (key, value),
+            _ => continue,
+        };
+        let key = key.split_whitespace().collect::<Vec<_>>().join(" ");
+        let value = match value
+            .split_whitespace()
+            .next()
+            .and_then(|v| v.replace(',', "").parse::<u64>().ok())
+        {
+            Some(value) => value,
+            None => continue,
+        };
+
+        let field = match key.as_str() {
+            "I refs" => &mut counts.instructions,
+            "D refs" => &mut counts.data_refs,
+            "D1 misses" => &mut counts.d1_misses,
+            "LLd misses" => &mut counts.lld_misses,
+            _ => continue,
+        };
+        *field = value;
+        found += 1;
+    }
+
+    if found == counts.metrics().len() {
+        Some(counts)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The stderr of `valgrind --tool=cachegrind --cache-sim=yes` in the format
+    /// of valgrind 3.15.
+    const SUMMARY: &str = "\
+==31337== Cachegrind, a cache and branch-prediction profiler
+==31337== Copyright (C) 2002-2017, and GNU GPL'd, by Nicholas Nethercote et al.
+==31337== Using Valgrind-3.15.0 and LibVEX; rerun with -h for copyright info
+==31337== Command: target/release/spp_experiments_bin kernel Vec by-reference 4096
+==31337== 
+--31337-- warning: L3 cache found, using its data for the LL simulation.
+==31337== 
+==31337== I   refs:      1,652,655
+==31337== I1  misses:        1,019
+==31337== LLi misses:        1,008
+==31337== I1  miss rate:      0.06%
+==31337== LLi miss rate:      0.06%
+==31337== 
+==31337== D   refs:        451,592  (339,064 rd   + 112,528 wr)
+==31337== D1  misses:       12,990  ( 10,370 rd   +   2,620 wr)
+==31337== LLd misses:        2,523  (  1,934 rd   +     589 wr)
+==31337== D1  miss rate:       2.9% (    3.1%     +     2.3%  )
+==31337== LLd miss rate:       0.6% (    0.6%     +     0.5%  )
+==31337== 
+==31337== LL refs:          14,009  ( 11,389 rd   +   2,620 wr)
+==31337== LL misses:         3,531  (  2,942 rd   +     589 wr)
+==31337== LL miss rate:        0.2% (    0.1%     +     0.5%  )
+";
+
+    #[test]
+    fn parses_the_cache_summary() {
+        assert_eq!(
+            parse_summary(SUMMARY),
+            Some(CacheCounts {
+                instructions: 1_652_655,
+                data_refs: 451_592,
+                d1_misses: 12_990,
+                lld_misses: 2_523,
+            })
+        );
+    }
+
+    #[test]
+    fn parses_lines_without_the_pid_prefix() {
+        let summary = "I refs: 10\nD refs: 4 (3 rd + 1 wr)\nD1 misses: 2\nLLd misses: 1\n";
+        assert_eq!(
+            parse_summary(summary),
+            Some(CacheCounts {
+                instructions: 10,
+                data_refs: 4,
+                d1_misses: 2,
+                lld_misses: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn is_none_without_every_count() {
+        // Without `--cache-sim=yes`, cachegrind only counts the instructions
+        let instructions_only = SUMMARY
+            .lines()
+            .filter(|line| !line.contains(" D") && !line.contains("LLd"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(parse_summary(&instructions_only), None);
+        assert_eq!(parse_summary(""), None);
+        assert_eq!(parse_summary("valgrind: command not found"), None);
+        assert_eq!(parse_summary(&SUMMARY.replace("12,990", "n/a")), None);
+    }
+
+    #[test]
+    fn subtracts_the_setup_counts() {
+        let kernel = CacheCounts {
+            instructions: 100,
+            data_refs: 50,
+            d1_misses: 10,
+            lld_misses: 1,
+        };
+        let setup = CacheCounts {
+            instructions: 60,
+            data_refs: 20,
+            d1_misses: 12,
+            lld_misses: 1,
+        };
+        assert_eq!(
+            kernel.saturating_sub(&setup),
+            CacheCounts {
+                instructions: 40,
+                data_refs: 30,
+                d1_misses: 0,
+                lld_misses: 0,
+            }
+        );
+    }
+}
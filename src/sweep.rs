@@ -0,0 +1,223 @@
+//! Input sizes around the cache capacities.
+//!
+//! The power-of-two sweep steps over the capacities of the caches, and with a
+//! step of 2^2 it can miss a discontinuity at a cache boundary entirely. The
+//! boundary sizes add points just below and above each capacity, and one
+//! level out on both sides. The range of the sweep follows from the caches as
+//! well, unless the experiment or `SOS_START_POW`, `SOS_END_POW` and
+//! `SOS_STEP_POW` override it.
+
+use crate::{aligned::CACHE_LINE, experiment::Experiment};
+use std::{env, io};
+
+/// Multiples of each cache capacity added to the sweep.
+pub const BOUNDARY_FACTORS: [f64; 4] = [0.5, 0.9, 1.1, 2.0];
+
+/// Data cache capacities of the test platform in bytes: 32 kB L1d, 256 kB L2
+/// and a 16 MB L3.
+pub const TEST_PLATFORM_CACHES: [usize; 3] = [32 * 1024, 256 * 1024, 16 * 1024 * 1024];
+
+/// The sweep starts this many powers of two below the smallest cache, e.g. at
+/// 1 kB for a 32 kB L1d.
+const POWS_BELOW_L1: u32 = 5;
+/// The sweep ends this many powers of two above the largest cache, e.g. at
+/// 64 MB for a 16 MB L3, where the inputs are well out of the caches.
+const POWS_ABOVE_LLC: u32 = 2;
+
+// Powers of two limits of the sweep on the test platform, for when the caches
+// can't be detected
+// 10 = 1 kB, 20 = 1 MB
+// 24 = 16 MB = L3 cache size on test platform
+// 26 = 64 MB = target
+pub const DEFAULT_START_POW: u32 = 10;
+pub const DEFAULT_END_POW: u32 = 26;
+pub const DEFAULT_STEP_POW: u32 = 2;
+
+/// Input sizes from `2^start_pow` to `2^end_pow` bytes, at every `step_pow`th
+/// power of two.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sweep {
+    pub start_pow: u32,
+    pub end_pow: u32,
+    pub step_pow: u32,
+}
+
+impl Sweep {
+    /// The sweep over `capacities`, with the limits and the step the experiment
+    /// or the environment set instead, the environment taking precedence.
+    pub fn resolve(experiment: &Experiment, capacities: &[usize]) -> io::Result<Self> {
+        let (start_pow, end_pow) =
+            pow_range(capacities).unwrap_or((DEFAULT_START_POW, DEFAULT_END_POW));
+        let sweep = Sweep {
+            start_pow: pow_from_env("SOS_START_POW", experiment.start_pow.unwrap_or(start_pow))?,
+            end_pow: end_pow_or(experiment, end_pow)?,
+            step_pow: pow_from_env(
+                "SOS_STEP_POW",
+                experiment.step_pow.unwrap_or(DEFAULT_STEP_POW),
+            )?,
+        };
+        if sweep.start_pow > sweep.end_pow || sweep.step_pow == 0 || sweep.end_pow >= usize::BITS {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "invalid sweep from 2^{} to 2^{} in steps of {}",
+                    sweep.start_pow, sweep.end_pow, sweep.step_pow
+                ),
+            ));
+        }
+
+        Ok(sweep)
+    }
+
+    /// The powers of two of the sweep in bytes, in ascending order.
+    pub fn sizes(&self) -> impl Iterator<Item = usize> {
+        (self.start_pow..=self.end_pow)
+            .step_by(self.step_pow as usize)
+            .map(|pow| 1 << pow)
+    }
+}
+
+/// Last power of two of a sweep that ends at `default` unless the experiment
+/// or `SOS_END_POW` sets the end, for the groups that extend the sweep.
+pub fn end_pow_or(experiment: &Experiment, default: u32) -> io::Result<u32> {
+    pow_from_env("SOS_END_POW", experiment.end_pow.unwrap_or(default))
+}
+
+/// The power of two in `var`, or `default` if it isn't set.
+fn pow_from_env(var: &str, default: u32) -> io::Result<u32> {
+    match env::var(var) {
+        Ok(pow) => pow.parse().map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid {}: {}: {}", var, pow, e),
+            )
+        }),
+        Err(_) => Ok(default),
+    }
+}
+
+/// First and last power of two of the sweep over `capacities`, from well
+/// within the smallest cache to well beyond the largest one.
+pub fn pow_range(capacities: &[usize]) -> Option<(u32, u32)> {
+    let smallest = capacities.iter().copied().filter(|&c| c > 0).min()?;
+    let largest = capacities.iter().copied().max()?;
+
+    let start_pow = smallest.ilog2().saturating_sub(POWS_BELOW_L1);
+    let end_pow = largest.next_power_of_two().ilog2() + POWS_ABOVE_LLC;
+    Some((start_pow, end_pow))
+}
+
+/// Sizes at the `BOUNDARY_FACTORS` of each of `capacities` within
+/// `min..=max`, in ascending order.
+///
+/// The sizes are rounded down to whole cache lines, so that they divide
+/// evenly into elements of every type.
+pub fn boundary_sizes(capacities: &[usize], min: usize, max: usize) -> Vec<usize> {
+    let mut sizes = capacities
+        .iter()
+        .flat_map(|&capacity| {
+            BOUNDARY_FACTORS
+                .iter()
+                .map(move |factor| (capacity as f64 * factor) as usize / CACHE_LINE * CACHE_LINE)
+        })
+        .filter(|&size| size > 0 && min <= size && size <= max)
+        .collect::<Vec<_>>();
+    sizes.sort_unstable();
+    sizes.dedup();
+
+    sizes
+}
+
+/// Parse a size with an optional binary unit suffix, e.g. `32k`, `256K`,
+/// `16M` or `1G`.
+pub fn parse_size(size: &str) -> Option<usize> {
+    let size = size.trim();
+    let (digits, unit) = match size.char_indices().last()? {
+        (i, c) if c.is_ascii_alphabetic() => (&size[..i], c.to_ascii_lowercase()),
+        _ => (size, 'b'),
+    };
+    let multiplier = match unit {
+        'b' => 1,
+        'k' => 1024,
+        'm' => 1024 * 1024,
+        'g' => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+
+    digits.trim().parse::<usize>().ok()?.checked_mul(multiplier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sizes_with_unit_suffixes() {
+        assert_eq!(parse_size("123"), Some(123));
+        assert_eq!(parse_size("64b"), Some(64));
+        assert_eq!(parse_size("32k"), Some(32 * 1024));
+        assert_eq!(parse_size("256K"), Some(256 * 1024));
+        assert_eq!(parse_size(" 16M "), Some(16 * 1024 * 1024));
+        assert_eq!(parse_size("1G"), Some(1024 * 1024 * 1024));
+    }
+
+    #[test]
+    fn rejects_invalid_sizes() {
+        for size in [
+            "",
+            "k",
+            "12x",
+            "1.5M",
+            "-1k",
+            "M16",
+            "99999999999999999999G",
+        ] {
+            assert_eq!(parse_size(size), None, "{:?}", size);
+        }
+    }
+
+    #[test]
+    fn rounds_boundary_sizes_down_to_cache_lines() {
+        // 500, 900, 1100 and 2000 bytes, in 64-byte lines
+        assert_eq!(
+            boundary_sizes(&[1000], 0, usize::MAX),
+            [448, 896, 1088, 1984]
+        );
+        assert!(boundary_sizes(&TEST_PLATFORM_CACHES, 0, usize::MAX)
+            .iter()
+            .all(|size| size % CACHE_LINE == 0));
+    }
+
+    #[test]
+    fn keeps_boundary_sizes_within_the_sweep() {
+        let (min, max) = (32 * 1024, 16 * 1024 * 1024);
+        let sizes = boundary_sizes(&TEST_PLATFORM_CACHES, min, max);
+
+        assert!(sizes.iter().all(|size| (min..=max).contains(size)));
+        assert!(sizes.windows(2).all(|pair| pair[0] < pair[1]));
+        // 1.1 times the L1d and 0.9 times the L3, in whole cache lines
+        assert_eq!(sizes.first(), Some(&36032));
+        assert_eq!(sizes.last(), Some(&15099456));
+    }
+
+    #[test]
+    fn sweeps_the_test_platform_from_1k_to_64m() {
+        assert_eq!(
+            pow_range(&TEST_PLATFORM_CACHES),
+            Some((DEFAULT_START_POW, DEFAULT_END_POW))
+        );
+        assert_eq!((DEFAULT_START_POW, DEFAULT_END_POW), (10, 26));
+    }
+
+    #[test]
+    fn falls_back_to_the_defaults_without_caches() {
+        assert_eq!(pow_range(&[]), None);
+        assert_eq!(pow_range(&[0]), None);
+
+        let sweep = Sweep::resolve(&Experiment::default(), &[]).unwrap();
+        assert_eq!(
+            (sweep.start_pow, sweep.end_pow, sweep.step_pow),
+            (DEFAULT_START_POW, DEFAULT_END_POW, DEFAULT_STEP_POW)
+        );
+    }
+}
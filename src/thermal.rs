@@ -0,0 +1,184 @@
+//! CPU temperature, frequency and thermal throttling, read from sysfs on Linux.
+//!
+//! Temperatures come from the thermal zones and throttling from the throttle
+//! event counters Intel CPUs keep per core and package. Either may be missing
+//! on a given machine, and is then left out of the checks.
+
+use crate::topology;
+use std::{
+    fmt, fs, io,
+    path::Path,
+    thread,
+    time::{Duration, Instant},
+};
+
+const THERMAL_DIR: &str = "/sys/class/thermal";
+
+/// Temperature that counts as throttling unless configured otherwise, in °C.
+pub const DEFAULT_MAX_TEMP_C: f64 = 85.0;
+/// A cooldown lasts until the temperature is this far below the maximum.
+const COOLDOWN_MARGIN_C: f64 = 10.0;
+/// Cooldown when only the throttle events are known.
+const COOLDOWN_PAUSE: Duration = Duration::from_secs(10);
+/// A cooldown never takes longer than this.
+const COOLDOWN_LIMIT: Duration = Duration::from_secs(120);
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Thermal state of the machine at one point in time.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Sample {
+    /// Highest temperature of any thermal zone in °C.
+    pub max_temp_c: Option<f64>,
+    /// Throttle events counted over all CPUs since boot.
+    pub throttle_events: Option<u64>,
+    /// Mean current frequency of the CPUs as a fraction of their maximum.
+    pub freq_fraction: Option<f64>,
+}
+
+impl Sample {
+    pub fn read() -> Self {
+        Sample {
+            max_temp_c: max_temp_c().ok().flatten(),
+            throttle_events: throttle_events().ok().flatten(),
+            freq_fraction: freq_fraction().ok().flatten(),
+        }
+    }
+}
+
+/// Why a check found the CPU throttled.
+#[derive(Clone, Copy, Debug)]
+pub enum Throttling {
+    /// The CPUs counted this many throttle events since the last check.
+    Events(u64),
+    /// A thermal zone reached this temperature in °C.
+    Temperature(f64),
+}
+
+impl fmt::Display for Throttling {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Throttling::Events(n) => write!(f, "{} throttle events", n),
+            Throttling::Temperature(t) => write!(f, "{:.0} °C", t),
+        }
+    }
+}
+
+/// Checks for throttling between benchmarks.
+pub struct Monitor {
+    max_temp_c: f64,
+    last: Sample,
+}
+
+impl Monitor {
+    pub fn new(max_temp_c: f64) -> Self {
+        Monitor {
+            max_temp_c,
+            last: Sample::read(),
+        }
+    }
+
+    /// Read the current state and compare it against the last check.
+    pub fn check(&mut self) -> (Sample, Option<Throttling>) {
+        let sample = Sample::read();
+        let events = sample
+            .throttle_events
+            .zip(self.last.throttle_events)
+            .map(|(now, last)| now.saturating_sub(last))
+            .filter(|&events| events > 0);
+        self.last = sample;
+
+        let throttling = match (events, sample.max_temp_c) {
+            (Some(events), _) => Some(Throttling::Events(events)),
+            (None, Some(temp)) if temp >= self.max_temp_c => Some(Throttling::Temperature(temp)),
+            _ => None,
+        };
+
+        (sample, throttling)
+    }
+
+    /// Wait until the temperature is well below the maximum, or for a fixed
+    /// pause if it isn't known. Returns the time waited.
+    pub fn cool_down(&mut self) -> Duration {
+        let start = Instant::now();
+        let target = self.max_temp_c - COOLDOWN_MARGIN_C;
+        match self.last.max_temp_c {
+            Some(_) => {
+                while start.elapsed() < COOLDOWN_LIMIT {
+                    thread::sleep(POLL_INTERVAL);
+                    match Sample::read().max_temp_c {
+                        Some(temp) if temp >= target => {}
+                        _ => break,
+                    }
+                }
+            }
+            None => thread::sleep(COOLDOWN_PAUSE),
+        }
+
+        // Throttling during the cooldown doesn't count against the next case
+        self.last = Sample::read();
+        start.elapsed()
+    }
+}
+
+fn max_temp_c() -> io::Result<Option<f64>> {
+    let mut max = None;
+    for entry in fs::read_dir(THERMAL_DIR)? {
+        let path = entry?.path();
+        let is_zone = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("thermal_zone"));
+        if !is_zone {
+            continue;
+        }
+
+        // Zones that can't be read right now, e.g. of a sleeping device, are
+        // skipped
+        if let Ok(millidegrees) = read_u64(&path.join("temp")) {
+            let temp = millidegrees as f64 / 1000.0;
+            max = Some(max.map_or(temp, |max: f64| max.max(temp)));
+        }
+    }
+
+    Ok(max)
+}
+
+fn throttle_events() -> io::Result<Option<u64>> {
+    let mut total = None;
+    for path in topology::cpu_dirs()? {
+        for counter in ["core_throttle_count", "package_throttle_count"].iter() {
+            if let Ok(count) = read_u64(&path.join("thermal_throttle").join(counter)) {
+                total = Some(total.unwrap_or(0) + count);
+            }
+        }
+    }
+
+    Ok(total)
+}
+
+fn freq_fraction() -> io::Result<Option<f64>> {
+    let mut fractions = Vec::new();
+    for path in topology::cpu_dirs()? {
+        let cpufreq = path.join("cpufreq");
+        if let (Ok(cur), Ok(max)) = (
+            read_u64(&cpufreq.join("scaling_cur_freq")),
+            read_u64(&cpufreq.join("cpuinfo_max_freq")),
+        ) {
+            if max > 0 {
+                fractions.push(cur as f64 / max as f64);
+            }
+        }
+    }
+
+    if fractions.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(fractions.iter().sum::<f64>() / fractions.len() as f64))
+}
+
+fn read_u64(path: &Path) -> io::Result<u64> {
+    fs::read_to_string(path)?
+        .trim()
+        .parse()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
@@ -0,0 +1,135 @@
+//! CPU topology, read from sysfs on Linux.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Sysfs directory of the CPUs.
+pub const CPU_DIR: &str = "/sys/devices/system/cpu";
+
+/// The sysfs directories of the logical CPUs, e.g. `cpu0`.
+pub fn cpu_dirs() -> io::Result<Vec<PathBuf>> {
+    let mut dirs = Vec::new();
+    for entry in fs::read_dir(CPU_DIR)? {
+        let path = entry?.path();
+        let is_cpu = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("cpu"))
+            .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()));
+        if is_cpu {
+            dirs.push(path);
+        }
+    }
+
+    Ok(dirs)
+}
+
+/// One logical CPU of each physical core, leaving out the SMT siblings.
+///
+/// Of each set of siblings, the lowest-numbered logical CPU is kept.
+pub fn physical_core_cpus() -> io::Result<Vec<usize>> {
+    let mut cpus = Vec::new();
+
+    for path in cpu_dirs()? {
+        // Offline CPUs have no topology
+        let siblings = match read_cpu_list(&path.join("topology/thread_siblings_list")) {
+            Ok(siblings) => siblings,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        if let Some(&first) = siblings.iter().min() {
+            cpus.push(first);
+        }
+    }
+
+    cpus.sort_unstable();
+    cpus.dedup();
+
+    Ok(cpus)
+}
+
+/// A data or unified cache of a CPU.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cache {
+    pub level: u32,
+    pub size_bytes: usize,
+}
+
+/// The data and unified caches of CPU 0, from the innermost level out.
+///
+/// The instruction caches are left out, as the benchmarks stream data.
+pub fn data_caches() -> io::Result<Vec<Cache>> {
+    let mut caches = Vec::new();
+
+    for entry in fs::read_dir(Path::new(CPU_DIR).join("cpu0/cache"))? {
+        let path = entry?.path();
+        let is_index = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("index"));
+        if !is_index {
+            continue;
+        }
+
+        let kind = fs::read_to_string(path.join("type"))?;
+        if kind.trim() == "Instruction" {
+            continue;
+        }
+
+        let level = fs::read_to_string(path.join("level"))?;
+        let size = fs::read_to_string(path.join("size"))?;
+        match (level.trim().parse(), parse_cache_size(size.trim())) {
+            (Ok(level), Some(size_bytes)) => caches.push(Cache { level, size_bytes }),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid cache description in {}", path.display()),
+                ))
+            }
+        }
+    }
+
+    caches.sort_unstable_by_key(|cache| cache.level);
+
+    Ok(caches)
+}
+
+/// Parse a cache size in sysfs' format, e.g. `32K`.
+fn parse_cache_size(size: &str) -> Option<usize> {
+    let (digits, multiplier) = match size.as_bytes().last()? {
+        b'K' => (&size[..size.len() - 1], 1024),
+        b'M' => (&size[..size.len() - 1], 1024 * 1024),
+        b'G' => (&size[..size.len() - 1], 1024 * 1024 * 1024),
+        _ => (size, 1),
+    };
+
+    digits.parse::<usize>().ok()?.checked_mul(multiplier)
+}
+
+/// Read a CPU list file in the kernel's list format, e.g. `0-3,8,10-11`.
+fn read_cpu_list(path: &Path) -> io::Result<Vec<usize>> {
+    let contents = fs::read_to_string(path)?;
+    parse_cpu_list(contents.trim()).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid CPU list in {}", path.display()),
+        )
+    })
+}
+
+fn parse_cpu_list(list: &str) -> Option<Vec<usize>> {
+    let mut cpus = Vec::new();
+    for range in list.split(',').filter(|range| !range.is_empty()) {
+        let mut bounds = range.splitn(2, '-');
+        let start = bounds.next()?.parse::<usize>().ok()?;
+        let end = match bounds.next() {
+            Some(end) => end.parse::<usize>().ok()?,
+            None => start,
+        };
+        cpus.extend(start..=end);
+    }
+
+    Some(cpus)
+}
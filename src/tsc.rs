@@ -0,0 +1,77 @@
+//! A criterion measurement reading the x86-64 time-stamp counter.
+//!
+//! On CPUs with an invariant TSC the counter ticks at the nominal frequency
+//! regardless of the current core frequency, so the cycle counts of runs at
+//! different clock speeds compare as reference cycles. Reads are fenced with
+//! `lfence` so that the kernel can't be reordered around them.
+
+use crate::measurement::CountFormatter;
+use criterion::measurement::{Measurement, ValueFormatter};
+use std::arch::x86_64::{__rdtscp, _mm_lfence, _rdtsc};
+
+/// Measures time-stamp counter cycles instead of wall time.
+pub struct TscCycles {
+    formatter: CountFormatter,
+}
+
+impl Default for TscCycles {
+    fn default() -> Self {
+        TscCycles {
+            formatter: CountFormatter {
+                unit: "cycles",
+                per_byte: "cycles/B",
+                per_element: "cycles/elem",
+            },
+        }
+    }
+}
+
+impl Measurement for TscCycles {
+    type Intermediate = u64;
+    type Value = u64;
+
+    fn start(&self) -> Self::Intermediate {
+        // SAFETY: rdtsc and lfence are available on every x86-64 CPU
+        unsafe {
+            // Wait for earlier instructions before reading
+            _mm_lfence();
+            let start = _rdtsc();
+            // Keep the measured instructions from starting before the read
+            _mm_lfence();
+            start
+        }
+    }
+
+    fn end(&self, start: Self::Intermediate) -> Self::Value {
+        let mut aux = 0;
+        // SAFETY: rdtscp is available on every x86-64 CPU this suite targets
+        let end = unsafe {
+            // rdtscp waits for earlier instructions itself
+            let end = __rdtscp(&mut aux);
+            _mm_lfence();
+            end
+        };
+
+        // The counters of different cores need not be in sync, so a thread
+        // moved to another core can read an end before its start. That
+        // iteration counts as no cycles rather than panicking, or wrapping
+        // around to an outlier of centuries.
+        end.saturating_sub(start)
+    }
+
+    fn add(&self, v1: &Self::Value, v2: &Self::Value) -> Self::Value {
+        v1 + v2
+    }
+
+    fn zero(&self) -> Self::Value {
+        0
+    }
+
+    fn to_f64(&self, value: &Self::Value) -> f64 {
+        *value as f64
+    }
+
+    fn formatter(&self) -> &dyn ValueFormatter {
+        &self.formatter
+    }
+}
@@ -0,0 +1,70 @@
+//! Manually unrolled variants of [`sum_of_squares_by_ref`].
+//!
+//! Each variant pulls a fixed number of elements from the iterator per loop
+//! iteration into independent accumulators, which breaks the dependency chain
+//! on a single sum. Whether LLVM already does this for a container depends on
+//! how well its iterator's `next` inlines and folds.
+//!
+//! [`sum_of_squares_by_ref`]: crate::sum_of_squares_by_ref
+
+use crate::Element;
+use std::iter;
+
+/// Define a by-reference kernel with one accumulator per identifier, i.e.
+/// unrolled by the number of accumulators.
+macro_rules! sum_of_squares_unrolled {
+    ($(#[$attr:meta])* $name:ident, $($acc:ident),+) => {
+        $(#[$attr])*
+        pub fn $name<V, T>(collection: &T) -> f64
+        where
+            T: ?Sized,
+            V: Element<f64>,
+            for<'a> &'a T: iter::IntoIterator<Item = &'a V>,
+        {
+            let mut iter = collection.into_iter();
+            $(let mut $acc = 0f64;)+
+
+            // The tail ends the loop part-way through, leaving the remaining
+            // accumulators as they were
+            'outer: loop {
+                $(
+                    match iter.next() {
+                        Some(x) => $acc += x.inner().powi(2),
+                        None => break 'outer,
+                    }
+                )+
+            }
+
+            0f64 $(+ $acc)+
+        }
+    };
+}
+
+sum_of_squares_unrolled!(
+    /// Sum the square of each input value, unrolled by 2.
+    sum_of_squares_unrolled_2,
+    acc0,
+    acc1
+);
+
+sum_of_squares_unrolled!(
+    /// Sum the square of each input value, unrolled by 4.
+    sum_of_squares_unrolled_4,
+    acc0,
+    acc1,
+    acc2,
+    acc3
+);
+
+sum_of_squares_unrolled!(
+    /// Sum the square of each input value, unrolled by 8.
+    sum_of_squares_unrolled_8,
+    acc0,
+    acc1,
+    acc2,
+    acc3,
+    acc4,
+    acc5,
+    acc6,
+    acc7
+);
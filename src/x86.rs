@@ -0,0 +1,104 @@
+//! Hand-written x86-64 intrinsic kernels, selected at runtime.
+//!
+//! Each kernel keeps four independent vector accumulators to hide the latency
+//! of the fused multiply-add, so these are close to the best a single core can
+//! do for this reduction.
+
+use std::arch::x86_64::*;
+
+/// Whether the AVX2 kernel can run on this CPU.
+pub fn avx2_available() -> bool {
+    is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma")
+}
+
+/// Whether the AVX-512 kernel can run on this CPU.
+pub fn avx512_available() -> bool {
+    is_x86_feature_detected!("avx512f")
+}
+
+/// Sum the square of each value using the widest kernel this CPU supports,
+/// falling back to the scalar loop.
+pub fn sum_of_squares_x86(data: &[f64]) -> f64 {
+    if avx512_available() {
+        sum_of_squares_avx512(data)
+    } else if avx2_available() {
+        sum_of_squares_avx2(data)
+    } else {
+        data.iter().map(|x| x.powi(2)).sum::<f64>()
+    }
+}
+
+/// Sum the square of each value with 256-bit AVX2 fused multiply-adds.
+///
+/// # Panics
+///
+/// If the CPU doesn't support AVX2 and FMA, see [`avx2_available`].
+pub fn sum_of_squares_avx2(data: &[f64]) -> f64 {
+    assert!(
+        avx2_available(),
+        "AVX2 and FMA are not supported on this CPU"
+    );
+
+    // SAFETY: the required target features were detected above
+    unsafe { sum_of_squares_avx2_fma(data) }
+}
+
+/// Sum the square of each value with 512-bit AVX-512 fused multiply-adds.
+///
+/// # Panics
+///
+/// If the CPU doesn't support AVX-512F, see [`avx512_available`].
+pub fn sum_of_squares_avx512(data: &[f64]) -> f64 {
+    assert!(avx512_available(), "AVX-512F is not supported on this CPU");
+
+    // SAFETY: the required target features were detected above
+    unsafe { sum_of_squares_avx512f(data) }
+}
+
+#[target_feature(enable = "avx2,fma")]
+unsafe fn sum_of_squares_avx2_fma(data: &[f64]) -> f64 {
+    const LANES: usize = 4;
+
+    let chunks = data.chunks_exact(4 * LANES);
+    let tail = chunks.remainder().iter().map(|x| x.powi(2)).sum::<f64>();
+
+    let mut sums = [_mm256_setzero_pd(); 4];
+    for chunk in chunks {
+        for (i, sum) in sums.iter_mut().enumerate() {
+            let x = _mm256_loadu_pd(chunk.as_ptr().add(i * LANES));
+            *sum = _mm256_fmadd_pd(x, x, *sum);
+        }
+    }
+
+    let sum = _mm256_add_pd(
+        _mm256_add_pd(sums[0], sums[1]),
+        _mm256_add_pd(sums[2], sums[3]),
+    );
+    let mut lanes = [0f64; LANES];
+    _mm256_storeu_pd(lanes.as_mut_ptr(), sum);
+
+    lanes.iter().sum::<f64>() + tail
+}
+
+#[target_feature(enable = "avx512f")]
+unsafe fn sum_of_squares_avx512f(data: &[f64]) -> f64 {
+    const LANES: usize = 8;
+
+    let chunks = data.chunks_exact(4 * LANES);
+    let tail = chunks.remainder().iter().map(|x| x.powi(2)).sum::<f64>();
+
+    let mut sums = [_mm512_setzero_pd(); 4];
+    for chunk in chunks {
+        for (i, sum) in sums.iter_mut().enumerate() {
+            let x = _mm512_loadu_pd(chunk.as_ptr().add(i * LANES));
+            *sum = _mm512_fmadd_pd(x, x, *sum);
+        }
+    }
+
+    let sum = _mm512_add_pd(
+        _mm512_add_pd(sums[0], sums[1]),
+        _mm512_add_pd(sums[2], sums[3]),
+    );
+
+    _mm512_reduce_add_pd(sum) + tail
+}